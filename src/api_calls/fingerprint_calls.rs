@@ -1,30 +1,20 @@
 use super::*;
-use crate::structures::fingerprint_structs::*;
-
-/// Calculate the CurseForge fingerprint for the `bytes` provided
-///
-/// CurseForge uses a modified version of [murmur2] where some bytes are stripped,
-/// and the resulting bytes are hashes with seed `1`
-pub fn cf_fingerprint(bytes: &[u8]) -> usize {
-    // Implement CF's murmur2 modification
-    let bytes = bytes
-        .iter()
-        .filter(|x| !matches!(x, 9 | 10 | 13 | 32))
-        .copied()
-        .collect::<Vec<u8>>();
-    // Hash the contents using seed `1`
-    murmur2::murmur2(&bytes, 1) as usize
-}
+use crate::{fingerprint::fingerprint_files, structures::fingerprint_structs::*};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 impl Furse {
     /// Get files and mod IDs from the `fingerprints` provided
     ///
     /// ## Example
     /// ```rust
+    /// # use furse::structures::{FileId, ModId};
     /// # tokio_test::block_on(async {
     /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
     /// // Get the Terralith mod's v2.0.12 file
-    /// let terralith_file = curseforge.get_mod_file(513688, 3606078).await?;
+    /// let terralith_file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
     /// // Download the file contents
     /// let contents = reqwest::get(terralith_file.download_url.unwrap())
     ///     .await?
@@ -42,12 +32,12 @@ impl Furse {
     /// ```
     pub async fn get_fingerprint_matches(
         &self,
-        fingerprints: Vec<usize>,
+        fingerprints: Vec<Fingerprint>,
     ) -> Result<FingerprintMatches> {
         #[derive(serde::Serialize)]
         #[serde(rename_all = "camelCase")]
         struct GetFingerprintMatchesRequestBody {
-            fingerprints: Vec<usize>,
+            fingerprints: Vec<Fingerprint>,
         }
 
         Ok(self
@@ -58,4 +48,139 @@ impl Furse {
             .await?
             .data)
     }
+
+    /// Get the mods whose files match the folder `fingerprints` provided, for the game with `game_id`
+    ///
+    /// Folder fingerprints can be calculated using [`folder_fingerprints()`](crate::fingerprint::folder_fingerprints).
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::structures::GameId;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// // Fingerprint the unpacked resource packs
+    /// let folders = furse::fingerprint::folder_fingerprints("resourcepacks", None)?;
+    /// // Find the resource packs that they belong to
+    /// let matches = curseforge
+    ///     .get_fingerprint_fuzzy_matches(GameId(432), folders)
+    ///     .await?;
+    /// for fuzzy_match in matches {
+    ///     println!("{} matches mod {}", fuzzy_match.file.display_name, fuzzy_match.id);
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn get_fingerprint_fuzzy_matches(
+        &self,
+        game_id: GameId,
+        fingerprints: Vec<FolderFingerprint>,
+    ) -> Result<Vec<FingerprintFuzzyMatch>> {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GetFuzzyMatchesRequestBody {
+            game_id: GameId,
+            fingerprints: Vec<FolderFingerprint>,
+        }
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct FingerprintFuzzyMatchResult {
+            fuzzy_matches: Vec<FingerprintFuzzyMatch>,
+        }
+
+        let result: FingerprintFuzzyMatchResult = self
+            .post(
+                API_URL_BASE.join("fingerprints/")?.join("fuzzy")?,
+                &GetFuzzyMatchesRequestBody {
+                    game_id,
+                    fingerprints,
+                },
+            )
+            .await?
+            .data;
+        Ok(result.fuzzy_matches)
+    }
+
+    /// Identify the local files at `paths` by fingerprinting them and looking up the fingerprints
+    ///
+    /// The results are in the same order as `paths`,
+    /// and include the mods that the matched files belong to.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::structures::fingerprint_structs::Identification;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let identified = curseforge
+    ///     .identify_files(vec!["mods/Terralith_1.18.2_v2.0.12.jar".into()])
+    ///     .await?;
+    /// let Identification::Exact(exact) = &identified[0].identification else {
+    ///     panic!("Expected an exact match");
+    /// };
+    /// assert_eq!(exact.owner.as_ref().unwrap().name, "Terralith");
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn identify_files(&self, paths: Vec<PathBuf>) -> Result<Vec<IdentifiedFile>> {
+        let (paths, fingerprints) =
+            tokio::task::spawn_blocking(move || fingerprint_files(&paths).map(|f| (paths, f)))
+                .await
+                .map_err(std::io::Error::other)??;
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let requested = fingerprints.iter().copied().collect::<HashSet<_>>();
+        let matches = self
+            .get_fingerprint_matches(requested.iter().copied().collect())
+            .await?;
+
+        let mut exact = HashMap::new();
+        for found in matches.exact_matches {
+            exact.insert(found.file.file_fingerprint, found);
+        }
+        let mut partial = HashMap::<_, Vec<_>>::new();
+        for found in matches.partial_matches {
+            // The fingerprints that matched are either listed separately, or in the file's modules
+            let mut matched = matches
+                .partial_match_fingerprints
+                .get(&found.file.id.to_string())
+                .cloned()
+                .unwrap_or_default();
+            matched.extend(found.file.modules.iter().flatten().map(|m| m.fingerprint));
+            matched.retain(|fingerprint| requested.contains(fingerprint));
+            matched.sort_unstable();
+            matched.dedup();
+            for fingerprint in matched {
+                partial.entry(fingerprint).or_default().push(found.clone());
+            }
+        }
+
+        let mods = self
+            .get_mods_by_id(
+                exact
+                    .values()
+                    .chain(partial.values().flatten())
+                    .map(|found| found.id),
+            )
+            .await?;
+        let identify = |found: &Match| IdentifiedMatch {
+            owner: mods.get(&found.id).cloned(),
+            file: found.file.clone(),
+            latest_files: found.latest_files.clone(),
+        };
+
+        Ok(paths
+            .into_iter()
+            .zip(fingerprints)
+            .map(|(path, fingerprint)| IdentifiedFile {
+                path,
+                fingerprint,
+                identification: if let Some(found) = exact.get(&fingerprint) {
+                    Identification::Exact(Box::new(identify(found)))
+                } else if let Some(found) = partial.get(&fingerprint) {
+                    Identification::Partial(found.iter().map(identify).collect())
+                } else {
+                    Identification::Unknown
+                },
+            })
+            .collect())
+    }
 }
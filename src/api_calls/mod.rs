@@ -1,5 +1,5 @@
 mod file_calls;
-pub(crate) mod fingerprint_calls;
+mod fingerprint_calls;
 mod mod_calls;
 
 use crate::{request::API_URL_BASE, structures::*, Furse, Result};
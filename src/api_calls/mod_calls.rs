@@ -1,20 +1,22 @@
 use super::*;
 use mod_structs::*;
+use std::collections::HashMap;
 
 impl Furse {
     /// Get mod with ID `mod_id`
     ///
     /// ## Example
     /// ```rust
+    /// # use furse::structures::ModId;
     /// # tokio_test::block_on(async {
     /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
     /// // Get the Terralith mod
-    /// let terralith_mod = curseforge.get_mod(513688).await?;
+    /// let terralith_mod = curseforge.get_mod(ModId(513688)).await?;
     /// // Check that it is made by Starmute
     /// assert_eq!(terralith_mod.authors[0].name, "Starmute");
     /// # Ok::<_, furse::Error>(()) }).unwrap()
     /// ```
-    pub async fn get_mod(&self, mod_id: ID) -> Result<Mod> {
+    pub async fn get_mod(&self, mod_id: ModId) -> Result<Mod> {
         Ok(self
             .get(API_URL_BASE.join("mods/")?.join(&mod_id.to_string())?)
             .await?
@@ -25,10 +27,11 @@ impl Furse {
     ///
     /// ## Example
     /// ```rust
+    /// # use furse::structures::ModId;
     /// # tokio_test::block_on(async {
     /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
     /// // Get Xaero's Minimap and World Map mods
-    /// let mods = curseforge.get_mods(vec![263420, 317780]).await?;
+    /// let mods = curseforge.get_mods(vec![ModId(263420), ModId(317780)]).await?;
     /// let [minimap, worldmap, ..] = mods.as_slice() else {
     ///     panic!("Expected 2 mods, got less");
     /// };
@@ -37,11 +40,11 @@ impl Furse {
     /// assert_eq!(worldmap.authors[0].name, "xaero96");
     /// # Ok::<_, furse::Error>(()) }).unwrap()
     /// ```
-    pub async fn get_mods(&self, mod_ids: Vec<ID>) -> Result<Vec<Mod>> {
+    pub async fn get_mods(&self, mod_ids: Vec<ModId>) -> Result<Vec<Mod>> {
         #[derive(serde::Serialize)]
         #[serde(rename_all = "camelCase")]
         struct GetModsByIdsListRequestBody {
-            mod_ids: Vec<ID>,
+            mod_ids: Vec<ModId>,
         }
         Ok(self
             .post(
@@ -52,19 +55,56 @@ impl Furse {
             .data)
     }
 
+    /// Get the mods with IDs `mod_ids`, keyed by their ID
+    ///
+    /// Duplicate IDs are only requested once, and no request is made if there are none.
+    /// Mods that don't exist are missing from the returned map.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::structures::ModId;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// // Get Xaero's Minimap and World Map mods
+    /// let mods = curseforge
+    ///     .get_mods_by_id([ModId(263420), ModId(317780), ModId(263420)])
+    ///     .await?;
+    /// assert_eq!(mods.len(), 2);
+    /// assert_eq!(mods[&ModId(317780)].authors[0].name, "xaero96");
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn get_mods_by_id(
+        &self,
+        mod_ids: impl IntoIterator<Item = ModId>,
+    ) -> Result<HashMap<ModId, Mod>> {
+        let mut mod_ids = mod_ids.into_iter().collect::<Vec<_>>();
+        mod_ids.sort_unstable();
+        mod_ids.dedup();
+        if mod_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(self
+            .get_mods(mod_ids)
+            .await?
+            .into_iter()
+            .map(|found| (found.id, found))
+            .collect())
+    }
+
     /// Get the description of mod with ID `mod_id`
     ///
     /// ## Example
     /// ```rust
+    /// # use furse::structures::ModId;
     /// # tokio_test::block_on(async {
     /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
     /// // Get the Terralith mod's description
-    /// let terralith_mod_description = curseforge.get_mod_description(513688).await?;
+    /// let terralith_mod_description = curseforge.get_mod_description(ModId(513688)).await?;
     /// // The description should contain the mod's name
     /// assert!(terralith_mod_description.contains("Terralith"));
     /// # Ok::<_, furse::Error>(()) }).unwrap()
     /// ```
-    pub async fn get_mod_description(&self, mod_id: ID) -> Result<String> {
+    pub async fn get_mod_description(&self, mod_id: ModId) -> Result<String> {
         Ok(self
             .get(
                 API_URL_BASE
@@ -0,0 +1,265 @@
+use super::{BandwidthLimiter, Cancellation, Transfer};
+use crate::{
+    structures::{file_structs::File, FileId},
+    Error, Furse, Result,
+};
+use futures_util::{stream, StreamExt};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A progress update sent by [`DownloadManager`]
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub file_id: FileId,
+    pub state: DownloadState,
+    /// The number of bytes downloaded across all files
+    pub total_downloaded: u64,
+    /// The combined size in bytes of all files
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadState {
+    Downloading {
+        /// The number of bytes of this file downloaded, including those from a previous partial download
+        downloaded: u64,
+        /// The size of this file in bytes
+        size: u64,
+    },
+    /// The download failed with `error`, and is being retried
+    Retrying { attempt: usize, error: String },
+    /// The file has been downloaded and verified
+    Finished,
+    /// The download failed with `error` and will not be retried
+    Failed { error: String },
+}
+
+type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// Downloads many files concurrently, resuming partial downloads and retrying failures
+///
+/// Files are downloaded and verified like in [`Furse::download_file()`],
+/// except that interrupted downloads are kept next to the destination with a `.part` extension.
+/// These are continued using HTTP range requests by later downloads to the same destination,
+/// even after a crash.
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::{download::DownloadManager, structures::FileId};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let files = curseforge.get_files(vec![FileId(3144153), FileId(3778436)]).await?;
+/// let manager = DownloadManager::new(curseforge)
+///     .concurrency(4)
+///     .bandwidth_limit(10 * 1024 * 1024)
+///     .on_progress(|progress| {
+///         println!("{}/{} bytes", progress.total_downloaded, progress.total_size)
+///     });
+/// let results = manager
+///     .download(
+///         files
+///             .into_iter()
+///             .flatten()
+///             .map(|file| {
+///                 let dest = format!("mods/{}", file.file_name).into();
+///                 (file, dest)
+///             })
+///             .collect(),
+///     )
+///     .await;
+/// for result in results {
+///     result?;
+/// }
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+#[derive(Clone)]
+pub struct DownloadManager {
+    furse: Furse,
+    concurrency: usize,
+    retries: usize,
+    retry_delay: Duration,
+    bandwidth_limit: Option<u64>,
+    on_progress: Option<ProgressCallback>,
+    cancellation: Arc<Cancellation>,
+}
+
+impl std::fmt::Debug for DownloadManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DownloadManager")
+            .field("furse", &self.furse)
+            .field("concurrency", &self.concurrency)
+            .field("retries", &self.retries)
+            .field("retry_delay", &self.retry_delay)
+            .field("bandwidth_limit", &self.bandwidth_limit)
+            .field("cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DownloadManager {
+    /// Create a download manager that downloads up to 8 files at a time,
+    /// and retries failed downloads 3 times starting after a 1 second delay
+    pub fn new(furse: Furse) -> Self {
+        Self {
+            furse,
+            concurrency: 8,
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+            bandwidth_limit: None,
+            on_progress: None,
+            cancellation: Arc::default(),
+        }
+    }
+
+    /// Set the maximum number of files downloaded at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the number of times a failed download is retried
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the delay before the first retry of a failed download, which is doubled for each further retry
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Limit the combined download speed to `bytes_per_second`
+    pub fn bandwidth_limit(mut self, bytes_per_second: u64) -> Self {
+        self.bandwidth_limit = Some(bytes_per_second);
+        self
+    }
+
+    /// Call `callback` whenever the progress of a download changes
+    pub fn on_progress(mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Stop all ongoing downloads, and make future downloads fail immediately
+    ///
+    /// Clones of this manager are cancelled too. Partial downloads are kept so that they can be resumed later.
+    /// Downloads waiting for a response, the next chunk, the bandwidth limit, or a retry are stopped straight away.
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Undo [`DownloadManager::cancel()`], so that failed downloads can be retried by passing them to
+    /// [`DownloadManager::download()`] again
+    pub fn reset(&self) {
+        self.cancellation.reset();
+    }
+
+    /// The client that files are downloaded with
+    pub(crate) fn furse(&self) -> &Furse {
+        &self.furse
+    }
+
+    /// Whether [`DownloadManager::cancel()`] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Download each file to its destination path
+    ///
+    /// The results are in the same order as `downloads`.
+    /// Failed downloads can be retried by passing them to this function again.
+    pub async fn download(&self, downloads: Vec<(File, PathBuf)>) -> Vec<Result<()>> {
+        let total_size = downloads
+            .iter()
+            .map(|(file, _)| file.file_length.max(0) as u64)
+            .sum();
+        let total_downloaded = AtomicU64::new(0);
+        let limiter = self.bandwidth_limit.map(BandwidthLimiter::new);
+        let report = |file_id, state| {
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(&Progress {
+                    file_id,
+                    state,
+                    total_downloaded: total_downloaded.load(Ordering::Relaxed),
+                    total_size,
+                });
+            }
+        };
+
+        let mut results = stream::iter(downloads.into_iter().enumerate())
+            .map(|(index, (file, dest))| {
+                let (report, total_downloaded, limiter) = (&report, &total_downloaded, &limiter);
+                async move {
+                    let size = file.file_length.max(0) as u64;
+                    let file_downloaded = AtomicU64::new(0);
+                    let on_progress = |downloaded: u64| {
+                        // Restarted downloads make the count go down, which wraps around correctly
+                        let previous = file_downloaded.swap(downloaded, Ordering::Relaxed);
+                        total_downloaded
+                            .fetch_add(downloaded.wrapping_sub(previous), Ordering::Relaxed);
+                        report(file.id, DownloadState::Downloading { downloaded, size });
+                    };
+                    let transfer = Transfer {
+                        resume: true,
+                        on_progress: Some(&on_progress),
+                        cancellation: Some(&self.cancellation),
+                        limiter: limiter.as_ref(),
+                    };
+
+                    let mut attempt = 0;
+                    let result = loop {
+                        if self.is_cancelled() {
+                            break Err(super::Cancelled.into());
+                        }
+                        if attempt > 0 {
+                            let delay = self.retry_delay.saturating_mul(1 << (attempt - 1).min(16));
+                            if let Err(err) = self.cancellation.run(tokio::time::sleep(delay)).await
+                            {
+                                break Err(err);
+                            }
+                        }
+                        match self.furse.transfer(&file, &dest, &transfer).await {
+                            Ok(()) => break Ok(()),
+                            Err(err @ (Error::Cancelled(_) | Error::ManualDownloadRequired(_))) => {
+                                break Err(err)
+                            }
+                            Err(err) if attempt < self.retries => {
+                                attempt += 1;
+                                report(
+                                    file.id,
+                                    DownloadState::Retrying {
+                                        attempt,
+                                        error: err.to_string(),
+                                    },
+                                );
+                            }
+                            Err(err) => break Err(err),
+                        }
+                    };
+                    match &result {
+                        Ok(()) => report(file.id, DownloadState::Finished),
+                        Err(err) => report(
+                            file.id,
+                            DownloadState::Failed {
+                                error: err.to_string(),
+                            },
+                        ),
+                    }
+                    (index, result)
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        results.sort_unstable_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
@@ -0,0 +1,153 @@
+use super::{source::website_file_url, verify_file};
+use crate::{structures::file_structs::File, Furse, Result};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::fs;
+use url::Url;
+
+/// A file that the user has to download in their browser
+#[derive(Debug, Clone)]
+pub struct PendingDownload {
+    pub file: File,
+    /// The file's page on the CurseForge website, to show to users
+    pub website_url: Url,
+    /// Where the file should be moved to once it has been downloaded
+    pub dest: PathBuf,
+}
+
+/// Tracks files that have to be downloaded manually, and picks them up once they have been downloaded
+///
+/// Downloaded files are recognised by their contents rather than their names,
+/// so they are found even if the browser renamed them.
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::structures::{FileId, ModId};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let file = curseforge.get_mod_file(ModId(238222), FileId(4371807)).await?;
+/// let dest = format!("mods/{}", file.file_name).into();
+/// let mut manual = curseforge.manual_downloads(vec![(file, dest)]).await?;
+/// for pending in manual.pending() {
+///     println!("Please download {} from {}", pending.file.file_name, pending.website_url);
+/// }
+/// manual
+///     .watch("Downloads", std::time::Duration::from_secs(1), |done| {
+///         println!("Found {}", done.file.file_name)
+///     })
+///     .await?;
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ManualDownloads {
+    pending: Vec<PendingDownload>,
+}
+
+impl Furse {
+    /// Prepare the manual download of `files`, which are moved to their paired destination once downloaded
+    ///
+    /// This is meant for files that resolve to [`DownloadSource::Manual`](super::DownloadSource::Manual).
+    /// The mods of the files are fetched to determine their pages on the CurseForge website.
+    pub async fn manual_downloads(&self, files: Vec<(File, PathBuf)>) -> Result<ManualDownloads> {
+        let mods = self
+            .get_mods_by_id(files.iter().map(|(file, _)| file.mod_id))
+            .await?;
+
+        let mut pending = Vec::with_capacity(files.len());
+        for (file, dest) in files {
+            let website_url = match mods.get(&file.mod_id) {
+                Some(owner) => website_file_url(owner, file.id)?,
+                None => self
+                    .get_mod(file.mod_id)
+                    .await
+                    .and_then(|owner| website_file_url(&owner, file.id))?,
+            };
+            pending.push(PendingDownload {
+                file,
+                website_url,
+                dest,
+            });
+        }
+        Ok(ManualDownloads { pending })
+    }
+}
+
+impl ManualDownloads {
+    /// The files that haven't been found yet
+    pub fn pending(&self) -> &[PendingDownload] {
+        &self.pending
+    }
+
+    /// Whether all the files have been found
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Look for pending files in `dir`, and move the ones found to their destinations
+    ///
+    /// Candidates are files with the expected length, which are then verified using
+    /// [`verify_file()`] to check their hashes and fingerprint.
+    /// Subdirectories are not searched. Returns the downloads that were completed.
+    pub async fn scan(&mut self, dir: impl AsRef<Path>) -> Result<Vec<PendingDownload>> {
+        let mut found = Vec::new();
+        let mut entries = fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let mut matched = None;
+            for (index, pending) in self.pending.iter().enumerate() {
+                if pending.file.file_length == metadata.len() as i64
+                    && verify_file(&pending.file, &path).await.is_ok()
+                {
+                    matched = Some(index);
+                    break;
+                }
+            }
+            if let Some(index) = matched {
+                let pending = self.pending.remove(index);
+                move_file(&path, &pending.dest).await?;
+                found.push(pending);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Scan `dir` every `interval` until all the files have been found, calling `on_found` for each one
+    pub async fn watch(
+        &mut self,
+        dir: impl AsRef<Path>,
+        interval: Duration,
+        mut on_found: impl FnMut(&PendingDownload),
+    ) -> Result<()> {
+        let dir = dir.as_ref();
+        while !self.is_complete() {
+            for download in self.scan(dir).await? {
+                on_found(&download);
+            }
+            if !self.is_complete() {
+                tokio::time::sleep(interval).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Move the file at `from` to `to`, copying it if they are on different file systems
+async fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    if fs::rename(from, to).await.is_err() {
+        fs::copy(from, to).await?;
+        fs::remove_file(from).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Downloading files and verifying their integrity
+
+mod manager;
+mod manual;
+mod source;
+mod verify;
+
+pub use manager::{DownloadManager, DownloadState, Progress};
+pub use manual::{ManualDownloads, PendingDownload};
+pub use source::{cdn_url, DownloadSource, ManualDownloadRequired};
+pub use verify::{verify_file, IntegrityError};
+
+use crate::{structures::file_structs::File, Error, Furse, Result};
+use reqwest::{header::RANGE, StatusCode};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{fs, io::AsyncWriteExt, sync::Notify};
+use verify::Verifier;
+
+/// A download that was stopped using [`DownloadManager::cancel()`]
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the download was cancelled")]
+pub struct Cancelled;
+
+/// The path that the contents of `dest` are written to while they are being downloaded
+pub(crate) fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// A cancellation flag that can also be awaited, shared by the downloads of a [`DownloadManager`]
+#[derive(Debug, Default)]
+pub(crate) struct Cancellation {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl Cancellation {
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.notify.notify_waiters();
+    }
+
+    pub(crate) fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Wait until the flag is set
+    async fn wait(&self) {
+        loop {
+            // Registered before checking the flag so that a concurrent `cancel()` isn't missed
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `future` to completion, unless the flag is set first
+    pub(crate) async fn run<T>(&self, future: impl Future<Output = T>) -> Result<T> {
+        tokio::select! {
+            biased;
+            () = self.wait() => Err(Cancelled.into()),
+            output = future => Ok(output),
+        }
+    }
+}
+
+/// Limits the combined speed of downloads by delaying them
+pub(crate) struct BandwidthLimiter {
+    bytes_per_second: u64,
+    /// When the limiter was created, and the number of bytes consumed since then
+    state: Mutex<(Instant, u64)>,
+}
+
+impl BandwidthLimiter {
+    pub(crate) fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Record that `bytes` were downloaded, and wait until doing so is within the limit
+    async fn consume(&self, bytes: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            state.1 += bytes;
+            Duration::from_secs_f64(state.1 as f64 / self.bytes_per_second as f64)
+                .saturating_sub(state.0.elapsed())
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Options for a single transfer
+#[derive(Default)]
+pub(crate) struct Transfer<'a> {
+    /// Continue from an existing partial download, and keep the partial download if the transfer fails
+    resume: bool,
+    /// Called with the number of bytes of the file downloaded so far
+    on_progress: Option<&'a (dyn Fn(u64) + Send + Sync)>,
+    cancellation: Option<&'a Cancellation>,
+    limiter: Option<&'a BandwidthLimiter>,
+}
+
+impl Transfer<'_> {
+    /// Run `future`, stopping early if the transfer is cancelled
+    async fn run<T>(&self, future: impl Future<Output = T>) -> Result<T> {
+        match self.cancellation {
+            Some(cancellation) => cancellation.run(future).await,
+            None => Ok(future.await),
+        }
+    }
+}
+
+impl Furse {
+    /// Download `file` to `dest`, and verify its length, hashes, and fingerprint
+    ///
+    /// The contents are streamed to a temporary file next to `dest`, which is moved to `dest` only once verified.
+    /// If verification fails, the temporary file is deleted and an [`IntegrityError`] is returned.
+    /// If the file has to be downloaded manually (see [`Furse::resolve_download()`]),
+    /// a [`ManualDownloadRequired`] error is returned.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::structures::{FileId, ModId};
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let terralith_file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
+    /// curseforge
+    ///     .download_file(&terralith_file, format!("mods/{}", terralith_file.file_name))
+    ///     .await?;
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn download_file(&self, file: &File, dest: impl AsRef<Path>) -> Result<()> {
+        self.transfer(file, dest.as_ref(), &Transfer::default())
+            .await
+    }
+
+    pub(crate) async fn transfer(
+        &self,
+        file: &File,
+        dest: &Path,
+        transfer: &Transfer<'_>,
+    ) -> Result<()> {
+        let temp = partial_path(dest);
+        let result = async {
+            let existing = if transfer.resume {
+                fs::metadata(&temp)
+                    .await
+                    .map_or(0, |metadata| metadata.len())
+            } else {
+                0
+            };
+            let mut verifier = Verifier::default();
+            let mut downloaded = 0;
+            let report = |downloaded| {
+                if let Some(on_progress) = transfer.on_progress {
+                    on_progress(downloaded);
+                }
+            };
+
+            // A previous attempt may have been interrupted after the download completed
+            if existing > 0 && existing as i64 >= file.file_length {
+                verifier.update_from(&temp).await?;
+                report(existing);
+                return verifier.verify(file, &temp).await;
+            }
+
+            let url = match self.resolve_download(file).await? {
+                DownloadSource::Api(url) | DownloadSource::Cdn(url) => url,
+                DownloadSource::Manual(manual) => return Err(Box::new(manual).into()),
+            };
+            let mut request = self.client.get(url);
+            if existing > 0 {
+                request = request.header(RANGE, format!("bytes={existing}-"));
+            }
+            let mut response = transfer.run(request.send()).await??.error_for_status()?;
+            let mut writer = if existing > 0 && response.status() == StatusCode::PARTIAL_CONTENT {
+                verifier.update_from(&temp).await?;
+                downloaded = existing;
+                fs::OpenOptions::new().append(true).open(&temp).await?
+            } else {
+                fs::File::create(&temp).await?
+            };
+            report(downloaded);
+
+            while let Some(chunk) = transfer.run(response.chunk()).await?? {
+                verifier.update(&chunk);
+                writer.write_all(&chunk).await?;
+                downloaded += chunk.len() as u64;
+                report(downloaded);
+                if let Some(limiter) = transfer.limiter {
+                    transfer.run(limiter.consume(chunk.len() as u64)).await?;
+                }
+            }
+            writer.sync_all().await?;
+            verifier.verify(file, &temp).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => Ok(fs::rename(&temp, dest).await?),
+            Err(err) => {
+                if !transfer.resume || matches!(err, Error::IntegrityError(_)) {
+                    let _ = fs::remove_file(&temp).await;
+                }
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    structures::{file_structs::File, mod_structs::Mod, FileId, ModId},
+    Furse, Result,
+};
+use url::Url;
+
+/// Where a file can be downloaded from, as determined by [`Furse::resolve_download()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadSource {
+    /// The download URL provided by the API
+    Api(Url),
+    /// A CDN URL derived using [`cdn_url()`], for mods that don't allow distribution through the API
+    Cdn(Url),
+    /// The file has to be downloaded manually from the CurseForge website
+    Manual(ManualDownloadRequired),
+}
+
+/// A file that can't be downloaded through the API, and has to be downloaded manually from the CurseForge website
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{file_name} has to be downloaded manually from {website_url}")]
+pub struct ManualDownloadRequired {
+    pub mod_id: ModId,
+    pub file_id: FileId,
+    /// The name the file will have when downloaded
+    pub file_name: String,
+    /// The file's page on the CurseForge website, to show to users
+    pub website_url: Url,
+}
+
+/// Derive the URL of `file` on CurseForge's CDN from its ID and name
+///
+/// CurseForge stores files at `https://edge.forgecdn.net/files/{id / 1000}/{id % 1000}/{file_name}`.
+/// This works for files of mods that don't allow distribution through the API,
+/// but it is not an official API, so it is only used by [`Furse::resolve_download()`]
+/// if enabled using [`Furse::with_cdn_fallback()`].
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::structures::{FileId, ModId};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let terralith_file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
+/// assert_eq!(
+///     furse::download::cdn_url(&terralith_file)?.as_str(),
+///     "https://edge.forgecdn.net/files/3606/78/Terralith_1.18.2_v2.0.12.jar",
+/// );
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+pub fn cdn_url(file: &File) -> Result<Url> {
+    let mut url = Url::parse("https://edge.forgecdn.net/files/")?;
+    url.path_segments_mut()
+        .expect("URL is a base")
+        .pop_if_empty()
+        .push(&(file.id.0 / 1000).to_string())
+        .push(&(file.id.0 % 1000).to_string())
+        .push(&file.file_name);
+    Ok(url)
+}
+
+/// The page of the file with `file_id` on the CurseForge website
+pub(crate) fn website_file_url(owner: &Mod, file_id: FileId) -> Result<Url> {
+    Ok(Url::parse(&format!(
+        "{}/files/{}",
+        owner.links.website_url.as_str().trim_end_matches('/'),
+        file_id
+    ))?)
+}
+
+impl Furse {
+    /// Enable or disable falling back to [`cdn_url()`] for files of mods that don't allow distribution through the API
+    ///
+    /// This is disabled by default, in which case such files resolve to [`DownloadSource::Manual`].
+    pub fn with_cdn_fallback(mut self, enabled: bool) -> Self {
+        self.cdn_fallback = enabled;
+        self
+    }
+
+    /// Determine where `file` can be downloaded from
+    ///
+    /// Files of mods with `allow_mod_distribution` set to `false` don't have a download URL.
+    /// For these, a CDN URL is returned if enabled using [`Furse::with_cdn_fallback()`],
+    /// otherwise the file's page on the CurseForge website is returned so that users can download it themselves.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::{download::DownloadSource, structures::{FileId, ModId}};
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let file = curseforge.get_mod_file(ModId(238222), FileId(4371807)).await?;
+    /// match curseforge.resolve_download(&file).await? {
+    ///     DownloadSource::Api(url) | DownloadSource::Cdn(url) => println!("Downloading from {url}"),
+    ///     DownloadSource::Manual(manual) => println!("Please download {}", manual.website_url),
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn resolve_download(&self, file: &File) -> Result<DownloadSource> {
+        if let Some(url) = &file.download_url {
+            return Ok(DownloadSource::Api(url.clone()));
+        }
+        if self.cdn_fallback {
+            return Ok(DownloadSource::Cdn(cdn_url(file)?));
+        }
+
+        let owner = self.get_mod(file.mod_id).await?;
+        if owner.allow_mod_distribution == Some(false) {
+            Ok(DownloadSource::Manual(ManualDownloadRequired {
+                mod_id: file.mod_id,
+                file_id: file.id,
+                file_name: file.file_name.clone(),
+                website_url: website_file_url(&owner, file.id)?,
+            }))
+        } else {
+            Ok(DownloadSource::Api(
+                self.file_download_url(file.mod_id, file.id).await?,
+            ))
+        }
+    }
+}
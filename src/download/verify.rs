@@ -0,0 +1,133 @@
+use crate::{
+    fingerprint::{normalised_len, FingerprintHasher},
+    structures::{
+        file_structs::{File, HashAlgo},
+        Fingerprint, Number,
+    },
+    Result,
+};
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use std::{io, path::Path};
+use tokio::{fs, io::AsyncReadExt};
+
+/// The size of the buffer used when reading files to verify
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A downloaded file that doesn't match the metadata of its [`File`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    #[error("expected a length of {expected} bytes, but the file is {actual} bytes long")]
+    Length { expected: Number, actual: Number },
+    #[error("expected a {algo:?} hash of {expected}, but the file's hash is {actual}")]
+    Hash {
+        algo: HashAlgo,
+        expected: String,
+        actual: String,
+    },
+    #[error("expected a fingerprint of {expected}, but the file's fingerprint is {actual}")]
+    Fingerprint {
+        expected: Fingerprint,
+        actual: Fingerprint,
+    },
+}
+
+/// Check that the contents of the local file at `path` match the length, hashes, and fingerprint of `file`
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::structures::{FileId, ModId};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let terralith_file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
+/// furse::download::verify_file(&terralith_file, "mods/Terralith_1.18.2_v2.0.12.jar").await?;
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+pub async fn verify_file(file: &File, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let mut verifier = Verifier::default();
+    verifier.update_from(path).await?;
+    verifier.verify(file, path).await
+}
+
+/// Calculates the length and hashes of a file as it is written
+#[derive(Default)]
+pub(crate) struct Verifier {
+    len: u64,
+    normalised_len: u32,
+    sha1: Sha1,
+    md5: Md5,
+}
+
+impl Verifier {
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        self.normalised_len = self.normalised_len.wrapping_add(normalised_len(bytes));
+        self.sha1.update(bytes);
+        self.md5.update(bytes);
+    }
+
+    /// Update with the contents of the local file at `path`
+    pub(crate) async fn update_from(&mut self, path: &Path) -> io::Result<()> {
+        let mut reader = fs::File::open(path).await?;
+        let mut buffer = vec![0; BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buffer).await? {
+                0 => break,
+                n => self.update(&buffer[..n]),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the bytes seen so far against `file`
+    ///
+    /// The fingerprint can only be calculated once the length of the input is known,
+    /// so the bytes are read again from `path`.
+    pub(crate) async fn verify(self, file: &File, path: &Path) -> Result<()> {
+        if self.len as Number != file.file_length {
+            return Err(IntegrityError::Length {
+                expected: file.file_length,
+                actual: self.len as Number,
+            }
+            .into());
+        }
+
+        let sha1 = format!("{:x}", self.sha1.finalize());
+        let md5 = format!("{:x}", self.md5.finalize());
+        for hash in &file.hashes {
+            let actual = match hash.algo {
+                HashAlgo::Sha1 => &sha1,
+                HashAlgo::Md5 => &md5,
+            };
+            if !hash.value.eq_ignore_ascii_case(actual) {
+                return Err(IntegrityError::Hash {
+                    algo: hash.algo,
+                    expected: hash.value.clone(),
+                    actual: actual.clone(),
+                }
+                .into());
+            }
+        }
+
+        let mut hasher = FingerprintHasher::new(self.normalised_len);
+        let mut reader = fs::File::open(path).await?;
+        let mut buffer = vec![0; BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buffer).await? {
+                0 => break,
+                n => hasher.update(&buffer[..n]),
+            }
+        }
+        let fingerprint = hasher.finish()?;
+        if fingerprint != file.file_fingerprint {
+            return Err(IntegrityError::Fingerprint {
+                expected: file.file_fingerprint,
+                actual: fingerprint,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use super::cf_fingerprint_reader;
+use crate::{structures::Fingerprint, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, Metadata},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Glob patterns deciding which files in a directory to fingerprint
+///
+/// Patterns are matched against paths relative to the directory being walked.
+/// A file is included if it matches any of the `include` patterns (or there are none),
+/// and none of the `exclude` patterns.
+///
+/// ## Example
+/// ```rust
+/// # use furse::fingerprint::PathFilter;
+/// let filter = PathFilter::new(["*.jar"], ["*-sources.jar"])?;
+/// assert!(filter.is_match("sodium.jar"));
+/// assert!(!filter.is_match("sodium-sources.jar"));
+/// assert!(!filter.is_match("options.txt"));
+/// # Ok::<_, furse::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    pub fn new<'a>(
+        include: impl IntoIterator<Item = &'a str>,
+        exclude: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self> {
+        let include = build_glob_set(include)?;
+        Ok(Self {
+            include: (!include.is_empty()).then_some(include),
+            exclude: build_glob_set(exclude)?,
+        })
+    }
+
+    /// Check whether the relative `path` passes this filter
+    pub fn is_match(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        self.include.as_ref().is_none_or(|set| set.is_match(path)) && !self.exclude.is_match(path)
+    }
+}
+
+fn build_glob_set<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// A persistent cache of fingerprints, keyed on the file's path, size, and modification time
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::fingerprint::{fingerprint_dir, FingerprintCache, PathFilter};
+/// let mut cache = FingerprintCache::load("fingerprints.json")?;
+/// let fingerprints = fingerprint_dir("mods", &PathFilter::default(), Some(&mut cache))?;
+/// cache.save("fingerprints.json")?;
+/// # Ok::<_, furse::Error>(())
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct FingerprintCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+struct CacheEntry {
+    size: u64,
+    modified: SystemTime,
+    fingerprint: Fingerprint,
+}
+
+impl FingerprintCache {
+    /// Load the cache stored at `path`, or create an empty one if it doesn't exist
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Store the cache at `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Get the cached fingerprint of the file at `path`, if its size and modification time haven't changed
+    pub fn get(&self, path: impl AsRef<Path>, metadata: &Metadata) -> Option<Fingerprint> {
+        let entry = self.entries.get(path.as_ref())?;
+        (entry.size == metadata.len() && Some(entry.modified) == metadata.modified().ok())
+            .then_some(entry.fingerprint)
+    }
+
+    /// Cache the `fingerprint` of the file at `path`
+    ///
+    /// Nothing is cached if the platform doesn't support modification times.
+    pub fn insert(
+        &mut self,
+        path: impl Into<PathBuf>,
+        metadata: &Metadata,
+        fingerprint: Fingerprint,
+    ) {
+        if let Ok(modified) = metadata.modified() {
+            self.entries.insert(
+                path.into(),
+                CacheEntry {
+                    size: metadata.len(),
+                    modified,
+                    fingerprint,
+                },
+            );
+        }
+    }
+
+    /// Remove the entries of files that no longer exist
+    pub fn prune(&mut self) {
+        self.entries.retain(|path, _| path.is_file());
+    }
+}
+
+/// Calculate the fingerprints of the files in `dir` and its subdirectories that pass `filter`
+///
+/// Files are hashed in parallel. If a `cache` is provided, files whose size and modification time
+/// haven't changed are not hashed again, and the newly calculated fingerprints are added to it.
+///
+/// The returned paths are `dir` joined with the file's relative path.
+/// Symbolic links to files are followed, but symbolic links to directories are not.
+/// This function blocks, so use something like `tokio::task::spawn_blocking` in asynchronous code.
+pub fn fingerprint_dir(
+    dir: impl AsRef<Path>,
+    filter: &PathFilter,
+    cache: Option<&mut FingerprintCache>,
+) -> Result<HashMap<PathBuf, Fingerprint>> {
+    let dir = dir.as_ref();
+    let mut files = Vec::new();
+    walk(dir, Path::new(""), filter, &mut files)?;
+
+    let mut fingerprints = HashMap::with_capacity(files.len());
+    let mut uncached = Vec::new();
+    for (path, metadata) in files {
+        match cache.as_ref().and_then(|cache| cache.get(&path, &metadata)) {
+            Some(fingerprint) => {
+                fingerprints.insert(path, fingerprint);
+            }
+            None => uncached.push((path, metadata)),
+        }
+    }
+
+    let hashed = uncached
+        .into_par_iter()
+        .map(|(path, metadata)| {
+            let fingerprint = cf_fingerprint_reader(BufReader::new(fs::File::open(&path)?))?;
+            Ok((path, metadata, fingerprint))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    if let Some(cache) = cache {
+        for (path, metadata, fingerprint) in &hashed {
+            cache.insert(path, metadata, *fingerprint);
+        }
+    }
+    fingerprints.extend(
+        hashed
+            .into_iter()
+            .map(|(path, _, fingerprint)| (path, fingerprint)),
+    );
+    Ok(fingerprints)
+}
+
+/// Calculate the fingerprints of the files at `paths` in parallel, in the same order
+pub fn fingerprint_files(paths: &[impl AsRef<Path> + Sync]) -> Result<Vec<Fingerprint>> {
+    Ok(paths
+        .par_iter()
+        .map(|path| cf_fingerprint_reader(BufReader::new(fs::File::open(path)?)))
+        .collect::<io::Result<_>>()?)
+}
+
+/// Recursively collect the files in `root.join(relative)` that pass `filter`
+pub(crate) fn walk(
+    root: &Path,
+    relative: &Path,
+    filter: &PathFilter,
+    files: &mut Vec<(PathBuf, Metadata)>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        let relative = relative.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(root, &relative, filter, files)?;
+        } else if filter.is_match(&relative) {
+            // Follow symbolic links to files
+            let metadata = fs::metadata(entry.path())?;
+            if metadata.is_file() {
+                files.push((entry.path(), metadata));
+            }
+        }
+    }
+    Ok(())
+}
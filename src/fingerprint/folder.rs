@@ -0,0 +1,53 @@
+use super::{fingerprint_dir, FingerprintCache, PathFilter};
+use crate::{structures::fingerprint_structs::FolderFingerprint, Result};
+use std::{fs, path::Path};
+
+/// Calculate the folder fingerprint of `folder`, for use with
+/// [`Furse::get_fingerprint_fuzzy_matches()`](crate::Furse::get_fingerprint_fuzzy_matches)
+///
+/// The result contains the folder's name and the [`cf_fingerprint()`](super::cf_fingerprint)
+/// of every file in it and its subdirectories, in ascending order.
+/// If a `cache` is provided, unchanged files are not hashed again.
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::fingerprint::folder_fingerprint;
+/// let fingerprint = folder_fingerprint("resourcepacks/Faithful", None)?;
+/// assert_eq!(fingerprint.folder_name, "Faithful");
+/// # Ok::<_, furse::Error>(())
+/// ```
+pub fn folder_fingerprint(
+    folder: impl AsRef<Path>,
+    cache: Option<&mut FingerprintCache>,
+) -> Result<FolderFingerprint> {
+    let folder = folder.as_ref();
+    let mut fingerprints = fingerprint_dir(folder, &PathFilter::default(), cache)?
+        .into_values()
+        .collect::<Vec<_>>();
+    fingerprints.sort_unstable();
+    Ok(FolderFingerprint {
+        folder_name: folder
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        fingerprints,
+    })
+}
+
+/// Calculate the folder fingerprints of every top level folder in `dir`
+///
+/// Files directly inside `dir` are ignored. See [`folder_fingerprint()`] for details.
+pub fn folder_fingerprints(
+    dir: impl AsRef<Path>,
+    mut cache: Option<&mut FingerprintCache>,
+) -> Result<Vec<FolderFingerprint>> {
+    let mut folders = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            folders.push(folder_fingerprint(entry.path(), cache.as_deref_mut())?);
+        }
+    }
+    folders.sort_unstable_by(|a, b| a.folder_name.cmp(&b.folder_name));
+    Ok(folders)
+}
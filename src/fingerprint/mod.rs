@@ -0,0 +1,244 @@
+//! Calculating CurseForge fingerprints of local files
+//!
+//! CurseForge uses a modified version of [murmur2](https://en.wikipedia.org/wiki/MurmurHash#MurmurHash2)
+//! where whitespace bytes (`\t`, `\n`, `\r` and ` `) are stripped,
+//! and the remaining bytes are hashed with seed `1`.
+//!
+//! Since murmur2 mixes the length of its input into the initial state,
+//! the number of non-whitespace bytes has to be known before hashing can start.
+//! This is why [`FingerprintHasher`] takes it as an argument,
+//! and why the reader helpers read their input twice instead of buffering it.
+
+mod dir;
+mod folder;
+
+pub(crate) use dir::walk;
+pub use dir::{fingerprint_dir, fingerprint_files, FingerprintCache, PathFilter};
+pub use folder::{folder_fingerprint, folder_fingerprints};
+
+use crate::structures::Fingerprint;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// The murmur2 mixing constant
+const M: u32 = 0x5bd1e995;
+/// The size of the buffer used when fingerprinting readers
+const BUFFER_SIZE: usize = 64 * 1024;
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, 9 | 10 | 13 | 32)
+}
+
+/// Calculate the CurseForge fingerprint for the `bytes` provided
+///
+/// CurseForge uses a modified version of [murmur2] where some bytes are stripped,
+/// and the resulting bytes are hashes with seed `1`
+///
+/// [murmur2]: https://en.wikipedia.org/wiki/MurmurHash#MurmurHash2
+pub fn cf_fingerprint(bytes: &[u8]) -> Fingerprint {
+    let mut hasher = FingerprintHasher::new(normalised_len(bytes));
+    hasher.update(bytes);
+    hasher.hash()
+}
+
+/// Count the bytes in `bytes` that are included in the fingerprint, i.e. the non-whitespace ones
+///
+/// Like CurseForge, the count wraps around for inputs larger than 4 GiB,
+/// so lengths of separate chunks should be combined using `wrapping_add`.
+pub fn normalised_len(bytes: &[u8]) -> u32 {
+    bytes.iter().filter(|&&byte| !is_whitespace(byte)).count() as u32
+}
+
+/// Calculate the CurseForge fingerprint of the contents of `reader`, from its current position to the end
+///
+/// The contents are read twice, once to count the non-whitespace bytes and once to hash them,
+/// using a fixed size buffer. This is why `reader` has to implement `Seek`:
+/// after the first pass, it is moved back to the position it started at.
+/// Contents that can't be read twice, such as from a socket or pipe, have to be read into memory
+/// and passed to [`cf_fingerprint()`] instead. The reader is left at the end of its contents.
+///
+/// ## Example
+/// ```rust
+/// # use furse::fingerprint::{cf_fingerprint, cf_fingerprint_reader};
+/// use std::io::{Cursor, Seek, SeekFrom};
+///
+/// let contents = b"Hello,\n world!\r\n";
+/// let fingerprint = cf_fingerprint_reader(Cursor::new(contents))?;
+/// assert_eq!(fingerprint, cf_fingerprint(contents));
+///
+/// // Only the contents after the reader's position are fingerprinted
+/// let mut reader = Cursor::new(contents);
+/// reader.seek(SeekFrom::Start(7))?;
+/// assert_eq!(cf_fingerprint_reader(&mut reader)?, cf_fingerprint(&contents[7..]));
+/// assert_eq!(reader.position(), contents.len() as u64);
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn cf_fingerprint_reader(mut reader: impl Read + Seek) -> io::Result<Fingerprint> {
+    let start = reader.stream_position()?;
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    let mut len = 0u32;
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => len = len.wrapping_add(normalised_len(&buffer[..n])),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    reader.seek(SeekFrom::Start(start))?;
+    let mut hasher = FingerprintHasher::new(len);
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    hasher.finish()
+}
+
+/// Calculate the CurseForge fingerprint of the contents of `reader` asynchronously,
+/// from its current position to the end
+///
+/// Like [`cf_fingerprint_reader()`], the contents are read twice using a fixed size buffer,
+/// so `reader` has to implement `AsyncSeek` to move back to the position it started at.
+///
+/// ## Example
+/// ```rust
+/// # use furse::fingerprint::{cf_fingerprint, cf_fingerprint_async_reader};
+/// # tokio_test::block_on(async {
+/// let contents = b"Hello,\n world!\r\n";
+/// let fingerprint = cf_fingerprint_async_reader(std::io::Cursor::new(contents)).await?;
+/// assert_eq!(fingerprint, cf_fingerprint(contents));
+///
+/// let mut reader = std::io::Cursor::new(contents);
+/// reader.set_position(7);
+/// let fingerprint = cf_fingerprint_async_reader(&mut reader).await?;
+/// assert_eq!(fingerprint, cf_fingerprint(&contents[7..]));
+/// # Ok::<_, std::io::Error>(()) }).unwrap()
+/// ```
+pub async fn cf_fingerprint_async_reader(
+    mut reader: impl AsyncRead + AsyncSeek + Unpin,
+) -> io::Result<Fingerprint> {
+    let start = reader.stream_position().await?;
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    let mut len = 0u32;
+    loop {
+        match reader.read(&mut buffer).await? {
+            0 => break,
+            n => len = len.wrapping_add(normalised_len(&buffer[..n])),
+        }
+    }
+
+    reader.seek(SeekFrom::Start(start)).await?;
+    let mut hasher = FingerprintHasher::new(len);
+    loop {
+        match reader.read(&mut buffer).await? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+    hasher.finish()
+}
+
+/// An incremental CurseForge fingerprint hasher
+///
+/// Whitespace bytes are skipped as they are written, so no intermediate buffer is needed.
+/// The number of non-whitespace bytes that will be written has to be provided upfront,
+/// it can be calculated using [`normalised_len()`].
+///
+/// ## Example
+/// ```rust
+/// # use furse::fingerprint::{cf_fingerprint, normalised_len, FingerprintHasher};
+/// use std::io::Write;
+///
+/// let (first, second) = (b"Hello,\n".as_slice(), b" world!\r\n".as_slice());
+/// let mut hasher = FingerprintHasher::new(normalised_len(first) + normalised_len(second));
+/// hasher.write_all(first)?;
+/// hasher.write_all(second)?;
+/// assert_eq!(hasher.finish()?, cf_fingerprint(b"Hello,\n world!\r\n"));
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct FingerprintHasher {
+    hash: u32,
+    /// Bytes that don't yet form a complete 4 byte block
+    tail: [u8; 4],
+    tail_len: usize,
+    /// The number of non-whitespace bytes still expected
+    remaining: u32,
+}
+
+impl FingerprintHasher {
+    /// Create a hasher for input with `normalised_len` non-whitespace bytes
+    pub fn new(normalised_len: u32) -> Self {
+        Self {
+            hash: 1 ^ normalised_len,
+            tail: [0; 4],
+            tail_len: 0,
+            remaining: normalised_len,
+        }
+    }
+
+    /// Hash `bytes`, skipping any whitespace
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if is_whitespace(byte) {
+                continue;
+            }
+            self.tail[self.tail_len] = byte;
+            self.tail_len += 1;
+            self.remaining = self.remaining.wrapping_sub(1);
+            if self.tail_len == 4 {
+                let mut k = u32::from_le_bytes(self.tail).wrapping_mul(M);
+                k ^= k >> 24;
+                self.hash = self.hash.wrapping_mul(M) ^ k.wrapping_mul(M);
+                self.tail_len = 0;
+            }
+        }
+    }
+
+    /// Get the fingerprint of the bytes written so far
+    ///
+    /// Returns an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if the number of non-whitespace bytes
+    /// written differs from the length given to [`FingerprintHasher::new()`], since the fingerprint would be wrong.
+    pub fn finish(&self) -> io::Result<Fingerprint> {
+        if self.remaining != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the number of bytes hashed does not match the length provided",
+            ));
+        }
+        Ok(self.hash())
+    }
+
+    /// Get the fingerprint of the bytes written so far, without checking their length
+    fn hash(&self) -> Fingerprint {
+        let mut hash = self.hash;
+        if self.tail_len > 0 {
+            let rest = self.tail[..self.tail_len]
+                .iter()
+                .rev()
+                .fold(0, |rest, &byte| rest << 8 | byte as u32);
+            hash = (hash ^ rest).wrapping_mul(M);
+        }
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(M);
+        Fingerprint(hash ^ hash >> 15)
+    }
+}
+
+impl Write for FingerprintHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
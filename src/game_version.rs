@@ -0,0 +1,483 @@
+//! Parsing, ordering, and matching Minecraft game versions
+//!
+//! [`GameVersion`] understands releases (`1.20.1`), pre-releases (`1.20-pre1`),
+//! release candidates (`1.20-rc1`), weekly snapshots (`23w13a`), numbered snapshots (`26.1-snapshot-1`),
+//! and CurseForge's snapshot game versions (`1.20-Snapshot`).
+//! [`GameVersionReq`] matches them against ranges such as `1.20.x` or `>=1.19.2, <1.21`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// A game version that could not be parsed
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid game version or range `{0}`")]
+pub struct GameVersionParseError(pub String);
+
+/// A release version number such as `1.20.1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Release {
+    pub major: u32,
+    pub minor: u32,
+    /// The patch version, which is `0` for versions such as `1.20`
+    pub patch: u32,
+}
+
+impl Release {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for Release {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Release {
+    type Err = GameVersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || GameVersionParseError(s.to_owned());
+        let mut parts = s.split('.');
+        let mut number = || -> Result<Option<u32>, GameVersionParseError> {
+            parts
+                .next()
+                .map(|part| part.parse().map_err(|_| err()))
+                .transpose()
+        };
+        let major = number()?.ok_or_else(err)?;
+        let minor = number()?.ok_or_else(err)?;
+        let patch = number()?.unwrap_or(0);
+        if number()?.is_some() {
+            return Err(err());
+        }
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+/// A Minecraft game version
+///
+/// Versions are ordered chronologically, so snapshots, pre-releases, and release candidates
+/// come before the release they lead up to.
+///
+/// ## Example
+/// ```rust
+/// # use furse::game_version::GameVersion;
+/// let mut versions = ["1.20.1", "1.20", "1.20-rc1", "23w13a", "1.19.4", "1.20-pre1"]
+///     .map(|version| version.parse::<GameVersion>().unwrap());
+/// versions.sort();
+/// assert_eq!(
+///     versions.map(|version| version.to_string()),
+///     ["1.19.4", "23w13a", "1.20-pre1", "1.20-rc1", "1.20", "1.20.1"],
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameVersion {
+    Release(Release),
+    /// A pre-release such as `1.20-pre1`
+    PreRelease(Release, u32),
+    /// A release candidate such as `1.20-rc1`
+    ReleaseCandidate(Release, u32),
+    /// A weekly snapshot such as `23w13a`
+    WeeklySnapshot {
+        year: u32,
+        week: u32,
+        build: char,
+    },
+    /// A snapshot of an upcoming release, either numbered such as `26.1-snapshot-1`,
+    /// or unnumbered such as CurseForge's `1.20-Snapshot` which stands for all of them
+    Snapshot(Release, Option<u32>),
+}
+
+/// The first weekly snapshot leading up to each release, used to order weekly snapshots relative to releases
+///
+/// This has to be updated when a new series of weekly snapshots starts, along with [`SNAPSHOT_SERIES_END`].
+const SNAPSHOT_SERIES: &[((u32, u32), Release)] = &[
+    ((11, 47), Release::new(1, 1, 0)),
+    ((12, 1), Release::new(1, 2, 1)),
+    ((12, 15), Release::new(1, 3, 1)),
+    ((12, 32), Release::new(1, 4, 2)),
+    ((12, 49), Release::new(1, 4, 6)),
+    ((13, 1), Release::new(1, 5, 0)),
+    ((13, 11), Release::new(1, 5, 1)),
+    ((13, 16), Release::new(1, 6, 1)),
+    ((13, 36), Release::new(1, 7, 2)),
+    ((13, 47), Release::new(1, 7, 4)),
+    ((14, 2), Release::new(1, 8, 0)),
+    ((15, 31), Release::new(1, 9, 0)),
+    ((16, 20), Release::new(1, 10, 0)),
+    ((16, 32), Release::new(1, 11, 0)),
+    ((16, 50), Release::new(1, 11, 1)),
+    ((17, 6), Release::new(1, 12, 0)),
+    ((17, 31), Release::new(1, 12, 1)),
+    ((17, 43), Release::new(1, 13, 0)),
+    ((18, 30), Release::new(1, 13, 1)),
+    ((18, 43), Release::new(1, 14, 0)),
+    ((19, 34), Release::new(1, 15, 0)),
+    ((20, 6), Release::new(1, 16, 0)),
+    ((20, 27), Release::new(1, 16, 2)),
+    ((20, 45), Release::new(1, 17, 0)),
+    ((21, 37), Release::new(1, 18, 0)),
+    ((22, 3), Release::new(1, 18, 2)),
+    ((22, 11), Release::new(1, 19, 0)),
+    ((22, 24), Release::new(1, 19, 1)),
+    ((22, 42), Release::new(1, 19, 3)),
+    ((23, 3), Release::new(1, 19, 4)),
+    ((23, 12), Release::new(1, 20, 0)),
+    ((23, 31), Release::new(1, 20, 2)),
+    ((23, 40), Release::new(1, 20, 3)),
+    ((24, 3), Release::new(1, 20, 5)),
+    ((24, 18), Release::new(1, 21, 0)),
+    ((24, 33), Release::new(1, 21, 2)),
+    ((24, 44), Release::new(1, 21, 4)),
+    ((25, 2), Release::new(1, 21, 5)),
+    ((25, 15), Release::new(1, 21, 6)),
+    ((25, 31), Release::new(1, 21, 9)),
+    ((25, 41), Release::new(1, 21, 11)),
+];
+
+/// The week after the last series in [`SNAPSHOT_SERIES`]
+///
+/// Weekly snapshots were replaced by numbered snapshots such as `26.1-snapshot-1` after 1.21.11,
+/// so weekly snapshots from this week onwards lead up to an unknown release.
+const SNAPSHOT_SERIES_END: (u32, u32) = (26, 1);
+
+impl GameVersion {
+    /// The release that this version is or leads up to
+    ///
+    /// This is `None` for weekly snapshots from before the first one, `11w47a`, and from after the last one
+    /// leading up to 1.21.11, as they can't be assigned to a release.
+    /// These can't be parsed, so they only exist if constructed directly.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::game_version::{GameVersion, Release};
+    /// let snapshot = "23w13a".parse::<GameVersion>()?;
+    /// assert_eq!(snapshot.release(), Some(Release::new(1, 20, 0)));
+    ///
+    /// let unknown = GameVersion::WeeklySnapshot { year: 26, week: 3, build: 'a' };
+    /// assert_eq!(unknown.release(), None);
+    /// assert!("26w03a".parse::<GameVersion>().is_err());
+    /// # Ok::<_, furse::game_version::GameVersionParseError>(())
+    /// ```
+    pub fn release(&self) -> Option<Release> {
+        match *self {
+            Self::Release(release)
+            | Self::PreRelease(release, _)
+            | Self::ReleaseCandidate(release, _)
+            | Self::Snapshot(release, _) => Some(release),
+            Self::WeeklySnapshot { year, week, .. } if (year, week) >= SNAPSHOT_SERIES_END => None,
+            Self::WeeklySnapshot { year, week, .. } => SNAPSHOT_SERIES
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= (year, week))
+                .map(|(_, release)| *release),
+        }
+    }
+
+    /// Whether this is a full release
+    pub fn is_release(&self) -> bool {
+        matches!(self, Self::Release(_))
+    }
+
+    /// The key that versions are ordered by
+    fn sort_key(&self) -> (Option<Release>, u8, u32, u32, u32) {
+        let stage = match *self {
+            Self::WeeklySnapshot { year, week, build } => (0, year * 100 + week, build as u32, 0),
+            Self::Snapshot(_, Some(number)) => (1, number, 0, 0),
+            Self::Snapshot(_, None) => (2, 0, 0, 0),
+            Self::PreRelease(_, number) => (3, number, 0, 0),
+            Self::ReleaseCandidate(_, number) => (4, number, 0, 0),
+            Self::Release(_) => (5, 0, 0, 0),
+        };
+        (self.release(), stage.0, stage.1, stage.2, stage.3)
+    }
+}
+
+impl Ord for GameVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for GameVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Release(release) => write!(f, "{release}"),
+            Self::PreRelease(release, number) => write!(f, "{release}-pre{number}"),
+            Self::ReleaseCandidate(release, number) => write!(f, "{release}-rc{number}"),
+            Self::WeeklySnapshot { year, week, build } => write!(f, "{year:02}w{week:02}{build}"),
+            Self::Snapshot(release, Some(number)) => write!(f, "{release}-snapshot-{number}"),
+            Self::Snapshot(release, None) => write!(f, "{release}-Snapshot"),
+        }
+    }
+}
+
+impl FromStr for GameVersion {
+    type Err = GameVersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || GameVersionParseError(s.to_owned());
+        let lower = s.trim().to_ascii_lowercase();
+
+        // Weekly snapshots, e.g. `23w13a`
+        if let Some((year, rest)) = lower.split_once('w') {
+            let mut chars = rest.chars();
+            let build = chars.next_back().ok_or_else(err)?;
+            let week = chars.as_str();
+            if year.len() == 2 && week.len() == 2 && build.is_ascii_lowercase() {
+                let snapshot = Self::WeeklySnapshot {
+                    year: year.parse().map_err(|_| err())?,
+                    week: week.parse().map_err(|_| err())?,
+                    build,
+                };
+                // Snapshots that don't lead up to a known release can't be ordered
+                if snapshot.release().is_some() {
+                    return Ok(snapshot);
+                }
+            }
+            return Err(err());
+        }
+
+        let (release, suffix) = match lower.split_once(['-', ' ']) {
+            Some((release, suffix)) => (release, Some(suffix)),
+            None => (lower.as_str(), None),
+        };
+        let release = release.parse::<Release>().map_err(|_| err())?;
+        let number = |number: &str| number.trim().parse::<u32>().map_err(|_| err());
+        match suffix {
+            None => Ok(Self::Release(release)),
+            Some("snapshot") => Ok(Self::Snapshot(release, None)),
+            Some(suffix) => {
+                if let Some(n) = suffix.strip_prefix("snapshot-") {
+                    Ok(Self::Snapshot(release, Some(number(n)?)))
+                } else if let Some(n) = suffix
+                    .strip_prefix("pre-release")
+                    .or_else(|| suffix.strip_prefix("pre"))
+                {
+                    Ok(Self::PreRelease(release, number(n)?))
+                } else if let Some(n) = suffix
+                    .strip_prefix("release candidate")
+                    .or_else(|| suffix.strip_prefix("rc"))
+                {
+                    Ok(Self::ReleaseCandidate(release, number(n)?))
+                } else {
+                    Err(err())
+                }
+            }
+        }
+    }
+}
+
+impl Serialize for GameVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Comparator {
+    Op(Op, GameVersion),
+    /// Versions whose release has this major version, and minor version if present
+    Wildcard {
+        major: u32,
+        minor: Option<u32>,
+    },
+}
+
+/// A range of game versions, such as `1.20.x` or `>=1.19.2, <1.21`
+///
+/// A range consists of comma separated comparators which all have to match.
+/// Comparators are a version optionally preceded by `=`, `>`, `>=`, `<`, or `<=`,
+/// or a wildcard such as `1.20.x` or `1.20.*` which matches versions that are or lead up to a `1.20` release,
+/// including snapshots such as `23w13a`, but not `1.21-pre1` or `24w18a`.
+/// Likewise, a release as the upper bound of `<` excludes the snapshots, pre-releases, and release candidates
+/// leading up to it, so `<1.21` doesn't match `1.21-pre1` or `24w18a` either.
+/// `*` matches every version.
+///
+/// ## Example
+/// ```rust
+/// # use furse::game_version::{GameVersion, GameVersionReq};
+/// let range = ">=1.19.2, <1.21".parse::<GameVersionReq>()?;
+/// assert!(range.matches(&"1.20.1".parse()?));
+/// assert!(range.matches(&"23w13a".parse()?));
+/// assert!(!range.matches(&"1.21".parse()?));
+/// assert!(!range.matches(&"1.21-pre1".parse()?));
+/// assert!(!range.matches(&"24w18a".parse()?));
+///
+/// let range = "1.20.x".parse::<GameVersionReq>()?;
+/// assert!(range.matches(&"1.20.6".parse()?));
+/// assert!(!range.matches(&"1.19.4".parse()?));
+/// assert!(!range.matches(&"1.21-pre1".parse()?));
+/// # Ok::<_, furse::game_version::GameVersionParseError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameVersionReq {
+    comparators: Vec<Comparator>,
+}
+
+impl GameVersionReq {
+    /// A range that matches every version
+    pub const ANY: Self = Self {
+        comparators: Vec::new(),
+    };
+
+    /// A range that matches exactly `version`
+    pub fn exact(version: GameVersion) -> Self {
+        Self {
+            comparators: vec![Comparator::Op(Op::Exact, version)],
+        }
+    }
+
+    /// Check whether `version` is in this range
+    pub fn matches(&self, version: &GameVersion) -> bool {
+        self.comparators.iter().all(|comparator| match *comparator {
+            Comparator::Op(op, bound) => match op {
+                Op::Exact => *version == bound,
+                Op::Greater => *version > bound,
+                Op::GreaterEq => *version >= bound,
+                Op::Less => {
+                    *version < bound
+                        && !(bound.is_release() && version.release() == bound.release())
+                }
+                Op::LessEq => *version <= bound,
+            },
+            Comparator::Wildcard { major, minor } => version.release().is_some_and(|release| {
+                release.major == major && minor.is_none_or(|minor| release.minor == minor)
+            }),
+        })
+    }
+
+    /// Check whether the game version string `version` is in this range, returning `false` if it can't be parsed
+    pub fn matches_str(&self, version: &str) -> bool {
+        version.parse().is_ok_and(|version| self.matches(&version))
+    }
+}
+
+impl fmt::Display for GameVersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return f.write_str("*");
+        }
+        for (i, comparator) in self.comparators.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let (op, version) = match comparator {
+                Comparator::Op(op, version) => (op, version),
+                Comparator::Wildcard { major, minor: None } => {
+                    write!(f, "{major}.x")?;
+                    continue;
+                }
+                Comparator::Wildcard {
+                    major,
+                    minor: Some(minor),
+                } => {
+                    write!(f, "{major}.{minor}.x")?;
+                    continue;
+                }
+            };
+            let op = match op {
+                Op::Exact => "=",
+                Op::Greater => ">",
+                Op::GreaterEq => ">=",
+                Op::Less => "<",
+                Op::LessEq => "<=",
+            };
+            write!(f, "{op}{version}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameVersionReq {
+    type Err = GameVersionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || GameVersionParseError(s.to_owned());
+        let mut comparators = Vec::new();
+        for comparator in s.split(',').map(str::trim) {
+            if comparator == "*" {
+                continue;
+            }
+            if let Some(prefix) = comparator
+                .strip_suffix(".x")
+                .or_else(|| comparator.strip_suffix(".*"))
+            {
+                let mut numbers = prefix
+                    .split('.')
+                    .map(|n| n.parse::<u32>().map_err(|_| err()));
+                let comparator = match (numbers.next(), numbers.next(), numbers.next()) {
+                    (Some(major), minor, None) => Comparator::Wildcard {
+                        major: major?,
+                        minor: minor.transpose()?,
+                    },
+                    _ => return Err(err()),
+                };
+                comparators.push(comparator);
+                continue;
+            }
+            let (op, version) = [
+                (">=", Op::GreaterEq),
+                ("<=", Op::LessEq),
+                (">", Op::Greater),
+                ("<", Op::Less),
+                ("=", Op::Exact),
+            ]
+            .into_iter()
+            .find_map(|(prefix, op)| Some((op, comparator.strip_prefix(prefix)?)))
+            .unwrap_or((Op::Exact, comparator));
+            comparators.push(Comparator::Op(
+                op,
+                version.trim().parse().map_err(|_| err())?,
+            ));
+        }
+        Ok(Self { comparators })
+    }
+}
+
+impl Serialize for GameVersionReq {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameVersionReq {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,533 @@
+//! Converting the HTML of mod descriptions and file changelogs to Markdown or plain text
+//!
+//! This module is only available with the `html` feature.
+
+use scraper::{ElementRef, Html, Node};
+use url::Url;
+
+/// The page that relative links in CurseForge's HTML are relative to
+const CURSEFORGE_URL: &str = "https://www.curseforge.com/";
+
+/// HTML converted to text, along with the URLs it contains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Converted {
+    pub text: String,
+    /// The targets of links, with CurseForge's redirects removed, in order of appearance
+    pub links: Vec<Url>,
+    /// The sources of images, excluding tracking images, in order of appearance
+    pub images: Vec<Url>,
+}
+
+/// Convert `html` to CommonMark
+///
+/// Links, lists, emphasis, headings, quotes, and code are kept,
+/// and text that would otherwise be read as Markdown syntax is escaped.
+/// Tracking images, which are at most one pixel in size, are dropped,
+/// and links through CurseForge's `linkout?remoteUrl=` redirect are replaced with their destination.
+///
+/// ## Example
+/// ```rust
+/// let converted = furse::html::to_markdown(
+///     r#"<p>Fixed <b>crashes</b> with <a href="/linkout?remoteUrl=https%253a%252f%252fgithub.com%252fexample">Example</a></p>
+///     <ul><li>Faster loading</li><li>New icon <img src="https://example.com/icon.png" alt="icon"></li></ul>
+///     <img src="https://example.com/pixel.gif" width="1" height="1">"#,
+/// );
+/// assert_eq!(
+///     converted.text,
+///     "Fixed **crashes** with [Example](https://github.com/example)\n\n- Faster loading\n- New icon ![icon](https://example.com/icon.png)",
+/// );
+/// assert_eq!(converted.links[0].as_str(), "https://github.com/example");
+/// assert_eq!(converted.images.len(), 1);
+///
+/// // Text that looks like Markdown syntax is escaped, empty headings are dropped,
+/// // and code is fenced with more backticks than it contains
+/// let converted = furse::html::to_markdown(
+///     r#"<p># Not a heading</p><p>&gt; Not a quote</p><p>1. Not a list</p><h2> </h2><p>Tom &amp; Jerry</p>
+///     <p>Use <code>a `b` c</code> or <code>`x</code></p>
+///     <ul><li><code>cargo build</code></li><li><pre>cargo ```test```</pre></li></ul>"#,
+/// );
+/// assert_eq!(
+///     converted.text,
+///     "\\# Not a heading\n\n\\> Not a quote\n\n1\\. Not a list\n\nTom \\& Jerry\n\n\
+///     Use ``a `b` c`` or `` `x ``\n\n- `cargo build`\n- ````\n  cargo ```test```\n  ````",
+/// );
+/// ```
+pub fn to_markdown(html: &str) -> Converted {
+    convert(html, None)
+}
+
+/// Convert `html` to plain text wrapped to `width` columns
+///
+/// Like [`to_markdown()`], except that formatting is dropped, links are written as `text (url)`,
+/// and images are replaced with their alt text.
+///
+/// ## Example
+/// ```rust
+/// let converted = furse::html::to_plain_text(
+///     r#"<h2>Changes</h2><ol><li>Fixed a crash when opening the <a href="https://example.com/config">config screen</a></li></ol>"#,
+///     40,
+/// );
+/// assert_eq!(
+///     converted.text,
+///     "Changes\n\n1. Fixed a crash when opening the config\n   screen (https://example.com/config)",
+/// );
+/// ```
+pub fn to_plain_text(html: &str, width: usize) -> Converted {
+    convert(html, Some(width.max(1)))
+}
+
+fn convert(html: &str, wrap_width: Option<usize>) -> Converted {
+    let fragment = Html::parse_fragment(html);
+    let mut renderer = Renderer {
+        wrap_width,
+        text: String::new(),
+        line: String::new(),
+        quote_depth: 0,
+        indents: Vec::new(),
+        lists: Vec::new(),
+        marker: None,
+        links: Vec::new(),
+        images: Vec::new(),
+    };
+    renderer.children(fragment.root_element());
+    renderer.flush();
+    Converted {
+        text: renderer.text.trim_end().to_owned(),
+        links: renderer.links,
+        images: renderer.images,
+    }
+}
+
+struct Renderer {
+    /// `None` to write Markdown, or the width to wrap plain text to
+    wrap_width: Option<usize>,
+    /// The finished lines
+    text: String,
+    /// The line being built
+    line: String,
+    quote_depth: usize,
+    /// The indentation of the content of each list item being rendered
+    indents: Vec<String>,
+    /// The number of the next item of each list being rendered, or `None` for unordered lists
+    lists: Vec<Option<usize>>,
+    /// The marker of the list item whose first line hasn't been written yet
+    marker: Option<String>,
+    links: Vec<Url>,
+    images: Vec<Url>,
+}
+
+impl Renderer {
+    fn markdown(&self) -> bool {
+        self.wrap_width.is_none()
+    }
+
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    self.element(ElementRef::wrap(child).expect("node is an element"))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        match name {
+            "script" | "style" | "noscript" | "head" | "title" => {}
+            "br" => self.hard_break(),
+            "p" | "div" | "section" | "article" | "header" | "footer" | "center" | "figure"
+            | "details" | "summary" | "table" => {
+                self.block_break();
+                self.children(element);
+                self.block_break();
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block_break();
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                let marker = "#".repeat(level);
+                if self.markdown() {
+                    self.line.push_str(&marker);
+                    self.line.push(' ');
+                }
+                self.children(element);
+                // Skip empty headings, which would otherwise be written as a bare `#`
+                if self.markdown() && self.line.trim() == marker {
+                    self.line.clear();
+                }
+                self.block_break();
+            }
+            "hr" => {
+                self.block_break();
+                self.line.push_str("---");
+                self.block_break();
+            }
+            "blockquote" => {
+                self.block_break();
+                self.quote_depth += 1;
+                self.children(element);
+                self.flush();
+                // Remove the empty line separating the quote's last block from the next one
+                let empty = format!("\n{}\n", self.prefix().trim_end());
+                if self.text.ends_with(&empty) {
+                    self.text.truncate(self.text.len() - empty.len() + 1);
+                }
+                self.quote_depth -= 1;
+                self.block_break();
+            }
+            "pre" => {
+                self.block_break();
+                let code = element.text().collect::<String>();
+                let prefix = self.prefix();
+                let indent = if self.markdown() { "" } else { "    " };
+                let mut lines = code
+                    .trim_matches('\n')
+                    .lines()
+                    .map(|line| format!("{indent}{line}"))
+                    .collect::<Vec<_>>();
+                if self.markdown() {
+                    let fence = fence(&code, 3);
+                    lines.insert(0, fence.clone());
+                    lines.push(fence);
+                }
+                for (i, line) in lines.iter().enumerate() {
+                    let prefix = if i == 0 {
+                        self.first_prefix()
+                    } else {
+                        prefix.clone()
+                    };
+                    self.push_line(&prefix, line);
+                }
+                self.block_break();
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.block_break();
+                } else {
+                    self.flush();
+                }
+                self.lists.push((name == "ol").then_some(1));
+                self.children(element);
+                self.lists.pop();
+                self.flush();
+                if self.lists.is_empty() {
+                    self.block_break();
+                }
+            }
+            "li" => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_owned(),
+                };
+                self.indents.push(" ".repeat(marker.len()));
+                self.marker = Some(marker);
+                self.children(element);
+                self.flush();
+                // Write empty items too
+                if let Some(marker) = self.marker.take() {
+                    let prefix = self.prefix();
+                    let indent_len = self.indents.last().map_or(0, String::len);
+                    self.push_line(&prefix[..prefix.len() - indent_len], marker.trim_end());
+                }
+                self.indents.pop();
+            }
+            "tr" => {
+                self.flush();
+                self.children(element);
+                self.flush();
+            }
+            "td" | "th" => {
+                if !self.line.trim().is_empty() {
+                    self.line.push_str(" | ");
+                }
+                self.children(element);
+            }
+            "b" | "strong" => self.emphasis(element, "**"),
+            "i" | "em" => self.emphasis(element, "*"),
+            "s" | "del" | "strike" => self.emphasis(element, "~~"),
+            "code" => {
+                let code = element.text().collect::<String>();
+                if self.markdown() {
+                    let fence = fence(&code, 1);
+                    // A space keeps backticks at the edges of the code from joining the fence
+                    let padding = if code.starts_with('`') || code.ends_with('`') {
+                        " "
+                    } else {
+                        ""
+                    };
+                    self.line
+                        .push_str(&format!("{fence}{padding}{code}{padding}{fence}"));
+                } else {
+                    self.line.push_str(&code);
+                }
+            }
+            "a" => self.link(element),
+            "img" => self.image(element),
+            "iframe" => {
+                if let Some(url) = element.attr("src").and_then(resolve) {
+                    let title = element.attr("title").unwrap_or(url.as_str()).to_owned();
+                    self.block_break();
+                    self.write_link(&title, url);
+                    self.block_break();
+                }
+            }
+            _ => self.children(element),
+        }
+    }
+
+    /// Append text, collapsing whitespace
+    fn text(&mut self, text: &str) {
+        let mut chars = text.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c.is_whitespace() {
+                if !self.line.is_empty() && !self.line.ends_with(' ') {
+                    self.line.push(' ');
+                }
+                continue;
+            }
+            // Keep text at the start of a line from being read as a heading, list, quote, or break
+            if self.markdown() && self.line.trim().is_empty() {
+                let rest = &text[i..];
+                let digits =
+                    rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                if digits > 0 && rest[digits..].starts_with(['.', ')']) {
+                    self.line.push_str(&rest[..digits]);
+                    self.line.push('\\');
+                    for _ in 1..digits {
+                        chars.next();
+                    }
+                    continue;
+                }
+                if matches!(c, '#' | '-' | '+' | '>' | '=' | '~') {
+                    self.line.push('\\');
+                    self.line.push(c);
+                    continue;
+                }
+            }
+            if self.markdown() && matches!(c, '\\' | '*' | '_' | '[' | ']' | '`' | '<' | '&') {
+                self.line.push('\\');
+                self.line.push(c);
+            } else {
+                self.line.push(c);
+            }
+        }
+    }
+
+    fn emphasis(&mut self, element: ElementRef, marker: &str) {
+        if !self.markdown() {
+            return self.children(element);
+        }
+        let start = self.line.len();
+        self.children(element);
+        if self.line.len() <= start || self.line[start..].trim().is_empty() {
+            return;
+        }
+        // Markers have to be next to the emphasised text
+        let start = start + self.line[start..].len() - self.line[start..].trim_start().len();
+        let trailing_space = self.line.ends_with(' ');
+        if trailing_space {
+            self.line.pop();
+        }
+        self.line.insert_str(start, marker);
+        self.line.push_str(marker);
+        if trailing_space {
+            self.line.push(' ');
+        }
+    }
+
+    fn link(&mut self, element: ElementRef) {
+        let start = self.line.len();
+        self.children(element);
+        let Some(url) = element.attr("href").and_then(resolve) else {
+            return;
+        };
+        let text = self.line[start..].trim().to_owned();
+        let leading_space = self.line[start..].starts_with(' ');
+        self.line.truncate(start);
+        if leading_space {
+            self.line.push(' ');
+        }
+        self.write_link(&text, url);
+    }
+
+    fn write_link(&mut self, text: &str, url: Url) {
+        if text.is_empty() || text.trim_end_matches('/') == url.as_str().trim_end_matches('/') {
+            if self.markdown() {
+                self.line.push_str(&format!("<{url}>"));
+            } else {
+                self.line.push_str(url.as_str());
+            }
+        } else if self.markdown() {
+            self.line.push_str(&format!("[{text}]({url})"));
+        } else {
+            self.line.push_str(&format!("{text} ({url})"));
+        }
+        if !self.links.contains(&url) {
+            self.links.push(url);
+        }
+    }
+
+    fn image(&mut self, element: ElementRef) {
+        let is_tracker = ["width", "height"].into_iter().any(|attr| {
+            element
+                .attr(attr)
+                .and_then(|size| size.trim_end_matches("px").trim().parse::<u32>().ok())
+                .is_some_and(|size| size <= 1)
+        });
+        let Some(url) = element.attr("src").and_then(resolve) else {
+            return;
+        };
+        if is_tracker {
+            return;
+        }
+        let alt = element.attr("alt").unwrap_or_default().trim();
+        if self.markdown() {
+            self.line.push_str(&format!("![{alt}]({url})"));
+        } else {
+            self.line.push_str(alt);
+        }
+        if !self.images.contains(&url) {
+            self.images.push(url);
+        }
+    }
+
+    /// The prefix of the next line, which contains the list item's marker if its first line hasn't been written yet
+    fn first_prefix(&mut self) -> String {
+        let prefix = self.prefix();
+        match self.marker.take() {
+            Some(marker) => {
+                let indent_len = self.indents.last().map_or(0, String::len);
+                format!("{}{marker}", &prefix[..prefix.len() - indent_len])
+            }
+            None => prefix,
+        }
+    }
+
+    /// The prefix of lines other than the first line of a list item
+    fn prefix(&self) -> String {
+        let mut prefix = "> ".repeat(self.quote_depth);
+        for indent in &self.indents {
+            prefix.push_str(indent);
+        }
+        prefix
+    }
+
+    fn push_line(&mut self, prefix: &str, content: &str) {
+        if content.is_empty() {
+            self.text.push_str(prefix.trim_end());
+        } else {
+            self.text.push_str(prefix);
+            self.text.push_str(content);
+        }
+        self.text.push('\n');
+    }
+
+    /// Finish the current line
+    fn flush(&mut self) {
+        let content = self.line.trim().to_owned();
+        self.line.clear();
+        // Keep the list item's marker for its first line of content
+        if content.is_empty() {
+            return;
+        }
+        let first_prefix = self.first_prefix();
+        let prefix = self.prefix();
+        match self.wrap_width {
+            Some(width) => {
+                // Don't break long words such as URLs
+                let options = textwrap::Options::new(width)
+                    .break_words(false)
+                    .word_splitter(textwrap::WordSplitter::NoHyphenation)
+                    .initial_indent(&first_prefix)
+                    .subsequent_indent(&prefix);
+                for line in textwrap::wrap(&content, options) {
+                    self.text.push_str(line.trim_end());
+                    self.text.push('\n');
+                }
+            }
+            None => {
+                self.text.push_str(&first_prefix);
+                self.text.push_str(&content);
+                self.text.push('\n');
+            }
+        }
+    }
+
+    /// Start a new line within the same block
+    fn hard_break(&mut self) {
+        if self.markdown() && !self.line.trim().is_empty() {
+            self.line.truncate(self.line.trim_end().len());
+            self.line.push('\\');
+        }
+        self.flush();
+    }
+
+    /// Separate blocks with an empty line, except within lists
+    fn block_break(&mut self) {
+        self.flush();
+        if self.lists.is_empty() && !self.text.is_empty() && !self.text.ends_with("\n\n") {
+            let prefix = self.prefix();
+            if !self.text.ends_with(&format!("{}\n", prefix.trim_end())) || prefix.is_empty() {
+                self.push_line(&prefix, "");
+            }
+        }
+    }
+}
+
+/// A code fence of backticks, longer than any run of backticks in `code` and at least `min` long
+fn fence(code: &str, min: usize) -> String {
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(min.max(longest + 1))
+}
+
+/// Resolve a link against CurseForge's website, replacing redirects through `linkout` with their destination
+fn resolve(href: &str) -> Option<Url> {
+    let url = Url::parse(CURSEFORGE_URL).ok()?.join(href.trim()).ok()?;
+    if url.path().ends_with("/linkout") {
+        if let Some((_, remote)) = url.query_pairs().find(|(key, _)| key == "remoteUrl") {
+            // CurseForge encodes the destination twice
+            let mut remote = remote.into_owned();
+            for _ in 0..3 {
+                if remote.contains("://") || !remote.contains('%') {
+                    break;
+                }
+                remote = percent_decode(&remote);
+            }
+            return Url::parse(&remote).ok();
+        }
+    }
+    Some(url)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,34 +1,153 @@
 #![doc = include_str!("../README.md")]
 
 mod api_calls;
-mod request;
+pub mod download;
+pub mod fingerprint;
+pub mod game_version;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod lockfile;
+pub mod modpack;
+pub mod request;
+pub mod resolve;
 pub mod structures;
-pub use api_calls::fingerprint_calls::cf_fingerprint;
+pub use fingerprint::cf_fingerprint;
 
 #[derive(thiserror::Error, Debug)]
 #[error(transparent)]
 pub enum Error {
     ReqwestError(#[from] reqwest::Error),
     JsonError(#[from] serde_json::Error),
+    DeserialisationError(#[from] Box<DeserialisationError>),
     UrlParseError(#[from] url::ParseError),
+    IoError(#[from] std::io::Error),
+    GlobError(#[from] globset::Error),
+    IntegrityError(#[from] download::IntegrityError),
+    Cancelled(#[from] download::Cancelled),
+    ManualDownloadRequired(#[from] Box<download::ManualDownloadRequired>),
+    ManifestError(#[from] modpack::ManifestError),
+    ZipError(#[from] zip::result::ZipError),
 }
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
+/// An API response that could not be deserialised into the expected structure
+///
+/// The error message includes the endpoint, the path to the value that failed
+/// (e.g. `data[312].latestFiles[0].modules`), and the value itself.
+///
+/// ## Example
+/// ```rust
+/// # use furse::structures::file_structs::File;
+/// # use std::collections::HashMap;
+/// let url = url::Url::parse("https://api.curseforge.com/v1/mods/1/files")?;
+/// // The hash's value should be a string
+/// let response = br#"{"data": [{
+///     "id": 7, "gameId": 432, "modId": 1, "isAvailable": true, "displayName": "Example",
+///     "fileName": "example.jar", "releaseType": 1, "fileStatus": 4,
+///     "hashes": [{ "value": 5, "algo": 1 }], "fileDate": "2024-01-01T00:00:00Z",
+///     "fileLength": 1, "downloadCount": 0, "downloadUrl": null, "gameVersions": [],
+///     "sortableGameVersions": [], "dependencies": [], "fileFingerprint": 0
+/// }]}"#;
+/// let Err(furse::Error::DeserialisationError(err)) =
+///     furse::request::deserialise::<HashMap<String, Vec<File>>>(url, response)
+/// else {
+///     panic!("the response should not deserialise");
+/// };
+/// assert_eq!(err.path, "data[0].hashes[0].value");
+/// assert_eq!(err.value, Some(serde_json::json!(5)));
+/// assert_eq!(err.entity_id, Some(7));
+/// # Ok::<_, url::ParseError>(())
+/// ```
+#[derive(thiserror::Error, Debug)]
+#[error(
+    "failed to deserialise `{path}`{} from {url}: {source}{}",
+    entity_id.map(|id| format!(" of entity {id}")).unwrap_or_default(),
+    value.as_ref().map(|value| format!(", found `{}`", truncate(value.to_string()))).unwrap_or_default(),
+)]
+pub struct DeserialisationError {
+    /// The URL of the endpoint that returned the response
+    pub url: url::Url,
+    /// The path to the offending value in the response
+    pub path: String,
+    /// The offending value, if the response was valid JSON
+    pub value: Option<serde_json::Value>,
+    /// The `id` of the closest object containing the offending value, such as a mod or file
+    pub entity_id: Option<structures::Number>,
+    pub source: serde_json::Error,
+}
+
+impl DeserialisationError {
+    pub(crate) fn new(
+        url: url::Url,
+        bytes: &[u8],
+        err: serde_path_to_error::Error<serde_json::Error>,
+    ) -> Box<Self> {
+        use serde_path_to_error::Segment;
+
+        let path = err.path().to_string();
+        let mut value = serde_json::from_slice::<serde_json::Value>(bytes).ok();
+        let mut entity_id = None;
+        // Walk down the path, remembering the `id` of every object passed through
+        for segment in err.path().iter() {
+            if let Some(id) = value.as_ref().and_then(|v| v.get("id")?.as_i64()) {
+                entity_id = Some(id);
+            }
+            value = match (segment, value) {
+                (Segment::Seq { index }, Some(serde_json::Value::Array(mut array)))
+                    if *index < array.len() =>
+                {
+                    Some(array.swap_remove(*index))
+                }
+                (Segment::Map { key }, Some(serde_json::Value::Object(mut object))) => {
+                    object.remove(key)
+                }
+                _ => None,
+            };
+        }
+        if let Some(id) = value.as_ref().and_then(|v| v.get("id")?.as_i64()) {
+            entity_id = Some(id);
+        }
+
+        Box::new(Self {
+            url,
+            path,
+            value,
+            entity_id,
+            source: err.into_inner(),
+        })
+    }
+}
+
+/// Shorten long values so that error messages stay readable
+fn truncate(mut string: String) -> String {
+    const MAX_LEN: usize = 100;
+    if string.len() > MAX_LEN {
+        let mut end = MAX_LEN;
+        while !string.is_char_boundary(end) {
+            end -= 1;
+        }
+        string.truncate(end);
+        string.push_str("...");
+    }
+    string
+}
+
 /// A container to store the API key and invoke API calls on
 ///
 /// ## Initialisation
 /// ```rust
-/// # use furse::Furse;
+/// # use furse::{Furse, structures::ModId};
 /// # tokio_test::block_on(async {
 /// let curseforge = Furse::new(env!("CURSEFORGE_API_KEY"));
 /// // Use the instance to call the API
-/// let terralith = curseforge.get_mod(513688).await?;
+/// let terralith = curseforge.get_mod(ModId(513688)).await?;
 /// # Ok::<_, furse::Error>(()) }).unwrap()
 /// ```
 #[derive(Clone, Debug)]
 pub struct Furse {
     client: reqwest::Client,
     api_key: String,
+    cdn_fallback: bool,
 }
 
 impl Furse {
@@ -44,6 +163,7 @@ impl Furse {
         Self {
             client: reqwest::Client::new(),
             api_key: api_key.into(),
+            cdn_fallback: false,
         }
     }
 }
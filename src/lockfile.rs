@@ -0,0 +1,318 @@
+//! Pinning the exact files of an instance so that it can be reproduced elsewhere
+
+use crate::{
+    fingerprint::{cf_fingerprint_reader, fingerprint_files},
+    resolve::Target,
+    structures::{
+        common_structs::ModLoaderType,
+        file_structs::{File, HashAlgo},
+        FileId, Fingerprint, ModId, Number,
+    },
+    Error, Furse, Result,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufReader, Seek},
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// The lockfile format version written by this version of furse
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// A JSON lockfile pinning the exact files installed in a directory, such as an instance's `mods` directory
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::{lockfile::Lockfile, resolve::Target, structures::{common_structs::ModLoaderType, FileId}};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let files = curseforge.get_files(vec![FileId(3606078)]).await?;
+/// let target = Target::new("1.18.2", ModLoaderType::Fabric);
+/// let lockfile = Lockfile::generate(&target, files.iter().flatten());
+/// lockfile.save("furse.lock")?;
+///
+/// // On another machine
+/// let lockfile = Lockfile::load("furse.lock")?;
+/// for action in curseforge.lockfile_actions(&lockfile, "mods").await? {
+///     println!("{action:?}");
+/// }
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Lockfile {
+    /// The format version, see [`LOCKFILE_VERSION`]
+    pub version: u32,
+    /// The game version, e.g. `1.20.1`
+    pub game_version: String,
+    #[serde(with = "loader_name")]
+    pub loader: ModLoaderType,
+    /// The pinned files, sorted by mod ID
+    pub files: Vec<LockedFile>,
+}
+
+/// A file pinned by a [`Lockfile`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedFile {
+    pub mod_id: ModId,
+    pub file_id: FileId,
+    /// The name of the file in the directory
+    pub file_name: String,
+    /// The file length in bytes
+    pub length: Number,
+    /// The SHA-1 hash in hexadecimal, if CurseForge provides one
+    pub sha1: Option<String>,
+    pub fingerprint: Fingerprint,
+    pub download_url: Option<Url>,
+}
+
+impl From<&File> for LockedFile {
+    fn from(file: &File) -> Self {
+        Self {
+            mod_id: file.mod_id,
+            file_id: file.id,
+            file_name: file.file_name.clone(),
+            length: file.file_length,
+            sha1: file
+                .hashes
+                .iter()
+                .find(|hash| hash.algo == HashAlgo::Sha1)
+                .map(|hash| hash.value.to_lowercase()),
+            fingerprint: file.file_fingerprint,
+            download_url: file.download_url.clone(),
+        }
+    }
+}
+
+/// How a file in a directory differs from a [`Lockfile`], as reported by [`Lockfile::verify()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The locked file is not in the directory
+    Missing(LockedFile),
+    /// The file in the directory has different contents than the locked file
+    Modified(LockedFile),
+    /// The file in the directory is not in the lockfile
+    Unexpected(PathBuf),
+}
+
+/// An action that brings a directory in line with a [`Lockfile`], as returned by [`Furse::lockfile_actions()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockAction {
+    /// Download the locked file, whose mod isn't in the directory
+    Add(LockedFile),
+    /// Remove the file at this path, which doesn't belong to any mod in the lockfile
+    Remove(PathBuf),
+    /// Replace the file at `path` with the locked file of the same mod, stored under the locked file's name
+    Update { path: PathBuf, file: LockedFile },
+    /// Move the file at `path`, which is the locked file under a different name, to the locked file's name
+    Rename { path: PathBuf, file: LockedFile },
+}
+
+impl Lockfile {
+    /// Pin `files` for `target`
+    pub fn generate<'a>(target: &Target, files: impl IntoIterator<Item = &'a File>) -> Self {
+        let mut files = files.into_iter().map(LockedFile::from).collect::<Vec<_>>();
+        files.sort_unstable_by_key(|file| (file.mod_id, file.file_id));
+        Self {
+            version: LOCKFILE_VERSION,
+            game_version: target.game_version.clone(),
+            loader: target.loader,
+            files,
+        }
+    }
+
+    /// Load the lockfile stored at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Store the lockfile at `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The game version and mod loader the files are pinned for
+    pub fn target(&self) -> Target {
+        Target::new(self.game_version.clone(), self.loader)
+    }
+
+    /// Check that the files directly in `dir` are exactly the locked files, by name, length, SHA-1 hash, and fingerprint
+    ///
+    /// Files are hashed in parallel, without loading them into memory.
+    /// After applying the actions from [`Furse::lockfile_actions()`], this reports no mismatches.
+    /// This function blocks, so use something like `tokio::task::spawn_blocking` in asynchronous code.
+    pub fn verify(&self, dir: impl AsRef<Path>) -> Result<Vec<Mismatch>> {
+        let dir = dir.as_ref();
+        let mut mismatches = self
+            .files
+            .par_iter()
+            .map(|locked| {
+                let file = match fs::File::open(dir.join(&locked.file_name)) {
+                    Ok(file) => file,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        return Ok(Some(Mismatch::Missing(locked.clone())))
+                    }
+                    Err(err) => return Err(err),
+                };
+                let modified = Some(Mismatch::Modified(locked.clone()));
+                if file.metadata()?.len() as Number != locked.length {
+                    return Ok(modified);
+                }
+                let mut reader = BufReader::new(file);
+                if cf_fingerprint_reader(&mut reader)? != locked.fingerprint {
+                    return Ok(modified);
+                }
+                if let Some(sha1) = &locked.sha1 {
+                    reader.rewind()?;
+                    let mut hasher = Sha1::new();
+                    io::copy(&mut reader, &mut hasher)?;
+                    if !format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(sha1) {
+                        return Ok(modified);
+                    }
+                }
+                Ok(None)
+            })
+            .collect::<io::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut unexpected = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file()
+                && !self
+                    .files
+                    .iter()
+                    .any(|locked| *locked.file_name == *entry.file_name())
+            {
+                unexpected.push(entry.path());
+            }
+        }
+        unexpected.sort_unstable();
+        mismatches.extend(unexpected.into_iter().map(Mismatch::Unexpected));
+        Ok(mismatches)
+    }
+}
+
+impl Furse {
+    /// Work out the actions that make the files directly in `dir` match `lockfile`
+    ///
+    /// Files are recognised by their fingerprints, so renamed files are moved back instead of being downloaded again.
+    /// Files that aren't locked are identified using [`Furse::get_fingerprint_matches()`],
+    /// so that files of locked mods are updated and the rest are removed.
+    ///
+    /// The actions should be applied in order: removals and updates come first, followed by renames and additions.
+    pub async fn lockfile_actions(
+        &self,
+        lockfile: &Lockfile,
+        dir: impl Into<PathBuf>,
+    ) -> Result<Vec<LockAction>> {
+        let dir = dir.into();
+        let mut present = tokio::task::spawn_blocking(move || {
+            // Only the files directly in `dir`, following symbolic links to files
+            let mut paths = Vec::new();
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if fs::metadata(&path)?.is_file() {
+                    paths.push(path);
+                }
+            }
+            let fingerprints = fingerprint_files(&paths)?;
+            Ok::<_, Error>(paths.into_iter().zip(fingerprints).collect::<Vec<_>>())
+        })
+        .await
+        .map_err(io::Error::other)??;
+        present.sort_unstable();
+
+        // Files under their locked name are matched first, so that copies under other names are removed
+        let mut missing = lockfile.files.iter().collect::<Vec<_>>();
+        present.retain(|(path, fingerprint)| {
+            let index = missing.iter().position(|locked| {
+                locked.fingerprint == *fingerprint
+                    && path.file_name() == Some(locked.file_name.as_ref())
+            });
+            if let Some(index) = index {
+                missing.swap_remove(index);
+            }
+            index.is_none()
+        });
+        let mut renamed = Vec::new();
+        let mut unlocked = Vec::new();
+        for (path, fingerprint) in present {
+            match missing
+                .iter()
+                .position(|locked| locked.fingerprint == fingerprint)
+            {
+                Some(index) => renamed.push(LockAction::Rename {
+                    path,
+                    file: missing.swap_remove(index).clone(),
+                }),
+                None => unlocked.push((path, fingerprint)),
+            }
+        }
+
+        let mut owners = HashMap::new();
+        if !unlocked.is_empty() {
+            let fingerprints = unlocked
+                .iter()
+                .map(|(_, fingerprint)| *fingerprint)
+                .collect();
+            for found in self
+                .get_fingerprint_matches(fingerprints)
+                .await?
+                .exact_matches
+            {
+                owners.insert(found.file.file_fingerprint, found.id);
+            }
+        }
+
+        let mut actions = Vec::new();
+        for (path, fingerprint) in unlocked {
+            let replacement = owners
+                .get(&fingerprint)
+                .and_then(|mod_id| missing.iter().position(|locked| locked.mod_id == *mod_id));
+            match replacement {
+                Some(index) => actions.push(LockAction::Update {
+                    path,
+                    file: missing.swap_remove(index).clone(),
+                }),
+                None => actions.push(LockAction::Remove(path)),
+            }
+        }
+        actions.extend(renamed);
+        missing.sort_unstable_by_key(|locked| locked.mod_id);
+        actions.extend(missing.into_iter().cloned().map(LockAction::Add));
+        Ok(actions)
+    }
+}
+
+/// (De)serialise mod loaders by their names rather than their numbers
+mod loader_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        loader: &ModLoaderType,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(loader.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<ModLoaderType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name.eq_ignore_ascii_case(ModLoaderType::Any.name()) {
+            return Ok(ModLoaderType::Any);
+        }
+        ModLoaderType::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown mod loader `{name}`")))
+    }
+}
@@ -0,0 +1,335 @@
+use super::{
+    InstallRecord, Manifest, ManifestFile, Minecraft, MinecraftInstance, INSTALL_RECORD_FILE,
+    INSTANCE_FILE, MANIFEST_TYPE,
+};
+use crate::{
+    fingerprint::{fingerprint_dir, walk, PathFilter},
+    structures::{file_structs::File, mod_structs::Mod, Fingerprint, JsonMap},
+    Furse, Result,
+};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+/// What to put into a modpack exported by [`Furse::export_modpack()`]
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub name: String,
+    /// The modpack's version
+    pub version: String,
+    pub author: String,
+    pub minecraft: Minecraft,
+    /// The directories in the instance whose files are identified on CurseForge,
+    /// defaults to `mods`, `resourcepacks`, and `shaderpacks`
+    pub content_dirs: Vec<PathBuf>,
+    /// Files and directories in the instance to include in the overrides, such as `config`
+    pub overrides: Vec<PathBuf>,
+    /// Which files in `content_dirs` and `overrides` to include, relative to each of them
+    pub filter: PathFilter,
+}
+
+impl ExportOptions {
+    /// Create options that export the content directories of an instance with `minecraft`'s game version and mod loaders
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        author: impl Into<String>,
+        minecraft: Minecraft,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            author: author.into(),
+            minecraft,
+            content_dirs: ["mods", "resourcepacks", "shaderpacks"]
+                .map(PathBuf::from)
+                .into(),
+            overrides: Vec::new(),
+            filter: PathFilter::default(),
+        }
+    }
+
+    /// Create options using the name, game version, and mod loaders of an instance installed by [`ModpackInstaller`](super::ModpackInstaller)
+    ///
+    /// The installed modpack's version is not reused, since the exported modpack is a new version.
+    /// Returns an error if the installed modpack's manifest had no name.
+    pub fn from_record(
+        record: &InstallRecord,
+        version: impl Into<String>,
+        author: impl Into<String>,
+    ) -> Result<Self> {
+        let name = record.name.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the install record has no modpack name",
+            )
+        })?;
+        Ok(Self::new(name, version, author, record.minecraft.clone()))
+    }
+
+    /// Create options using the name, game version, and base mod loader of a CurseForge app instance
+    pub fn from_instance(
+        instance: &MinecraftInstance,
+        version: impl Into<String>,
+        author: impl Into<String>,
+    ) -> Self {
+        Self::new(instance.name.clone(), version, author, instance.minecraft())
+    }
+
+    /// Create options for the instance directory `instance`, using its [`InstallRecord`] if it has one,
+    /// or its CurseForge app [`MinecraftInstance`] otherwise
+    pub fn load(
+        instance: impl AsRef<Path>,
+        version: impl Into<String>,
+        author: impl Into<String>,
+    ) -> Result<Self> {
+        let instance = instance.as_ref();
+        if instance.join(INSTALL_RECORD_FILE).is_file() {
+            Self::from_record(&InstallRecord::load(instance)?, version, author)
+        } else if instance.join(INSTANCE_FILE).is_file() {
+            Ok(Self::from_instance(
+                &MinecraftInstance::load(instance)?,
+                version,
+                author,
+            ))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "the instance has neither an install record nor a CurseForge app instance file",
+            )
+            .into())
+        }
+    }
+}
+
+/// The outcome of exporting a modpack
+#[derive(Debug, Clone)]
+pub struct ExportReport {
+    /// The manifest written to the modpack
+    pub manifest: Manifest,
+    /// The files written to the overrides, relative to the instance directory
+    pub overrides: Vec<PathBuf>,
+    /// The files in the content directories that were not found on CurseForge, and were added to the overrides
+    pub unmatched: Vec<PathBuf>,
+    /// The files in the content directories that were found on CurseForge, but belong to a mod
+    /// that another file is already listed for, and were added to the overrides
+    pub duplicates: Vec<PathBuf>,
+}
+
+impl Furse {
+    /// Export the instance at `instance` as a CurseForge modpack zip at `dest`
+    ///
+    /// Files in the content directories are identified using their fingerprints,
+    /// and listed in the manifest if they are found on CurseForge, or added to the overrides otherwise.
+    /// Since a manifest can only list one file per mod, further files of the same mod are added to the overrides too.
+    /// A `modlist.html` linking to the listed mods is included too.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::modpack::ExportOptions;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let mut options = ExportOptions::load("instance", "1.0.0", "Me")?;
+    /// options.overrides = vec!["config".into(), "options.txt".into()];
+    /// let report = curseforge
+    ///     .export_modpack("instance", &options, "modpack.zip")
+    ///     .await?;
+    /// for path in report.unmatched {
+    ///     println!("{} is not on CurseForge, it was added to the overrides", path.display());
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn export_modpack(
+        &self,
+        instance: impl Into<PathBuf>,
+        options: &ExportOptions,
+        dest: impl Into<PathBuf>,
+    ) -> Result<ExportReport> {
+        let (instance, dest) = (instance.into(), dest.into());
+
+        let (content, mut overrides) = {
+            let (instance, options) = (instance.clone(), options.clone());
+            tokio::task::spawn_blocking(move || collect_instance(&instance, &options))
+                .await
+                .map_err(io::Error::other)??
+        };
+
+        let mut matches = HashMap::new();
+        if !content.is_empty() {
+            let mut fingerprints = content.values().copied().collect::<Vec<_>>();
+            fingerprints.sort_unstable();
+            fingerprints.dedup();
+            for found in self
+                .get_fingerprint_matches(fingerprints)
+                .await?
+                .exact_matches
+            {
+                matches.insert(found.file.file_fingerprint, found.file);
+            }
+        }
+
+        let mut content = content.into_iter().collect::<Vec<_>>();
+        content.sort_unstable();
+        let mut files: Vec<File> = Vec::new();
+        let mut unmatched = Vec::new();
+        let mut duplicates = Vec::new();
+        for (path, fingerprint) in content {
+            match matches.get(&fingerprint) {
+                Some(file) if files.iter().any(|other| other.mod_id == file.mod_id) => {
+                    duplicates.push(path)
+                }
+                Some(file) => files.push(file.clone()),
+                None => unmatched.push(path),
+            }
+        }
+        overrides.extend(unmatched.iter().cloned());
+        overrides.extend(duplicates.iter().cloned());
+        overrides.sort_unstable();
+        overrides.dedup();
+
+        let mut mods = self
+            .get_mods_by_id(files.iter().map(|file| file.mod_id))
+            .await?
+            .into_values()
+            .collect::<Vec<_>>();
+        mods.sort_unstable_by_key(|listed| listed.name.to_lowercase());
+
+        let manifest = Manifest {
+            minecraft: options.minecraft.clone(),
+            manifest_type: MANIFEST_TYPE.to_owned(),
+            manifest_version: 1,
+            name: Some(options.name.clone()),
+            version: Some(options.version.clone()),
+            author: Some(options.author.clone()),
+            files: files
+                .iter()
+                .map(|file| ManifestFile {
+                    project_id: file.mod_id,
+                    file_id: file.id,
+                    required: true,
+                    extra: JsonMap::new(),
+                })
+                .collect(),
+            overrides: "overrides".to_owned(),
+            extra: JsonMap::new(),
+        };
+        manifest.validate()?;
+
+        {
+            let manifest = serde_json::to_vec_pretty(&manifest)?;
+            let modlist = modlist_html(&mods);
+            let (instance, overrides) = (instance.clone(), overrides.clone());
+            tokio::task::spawn_blocking(move || {
+                write_modpack(&dest, &instance, &manifest, &modlist, &overrides)
+            })
+            .await
+            .map_err(io::Error::other)??;
+        }
+
+        Ok(ExportReport {
+            manifest,
+            overrides,
+            unmatched,
+            duplicates,
+        })
+    }
+}
+
+/// Fingerprint the files in the content directories, and list the files to include in the overrides
+fn collect_instance(
+    instance: &Path,
+    options: &ExportOptions,
+) -> Result<(HashMap<PathBuf, Fingerprint>, Vec<PathBuf>)> {
+    let mut content = HashMap::new();
+    for dir in &options.content_dirs {
+        let dir = instance.join(dir);
+        if dir.is_dir() {
+            for (path, fingerprint) in fingerprint_dir(&dir, &options.filter, None)? {
+                content.insert(relative_to(instance, &path), fingerprint);
+            }
+        }
+    }
+
+    let mut overrides = Vec::new();
+    for path in &options.overrides {
+        let full = instance.join(path);
+        if full.is_dir() {
+            let mut files = Vec::new();
+            walk(&full, Path::new(""), &options.filter, &mut files)?;
+            overrides.extend(files.iter().map(|(file, _)| relative_to(instance, file)));
+        } else if full.is_file() {
+            overrides.push(path.clone());
+        }
+    }
+    Ok((content, overrides))
+}
+
+fn relative_to(base: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(base).unwrap_or(path).to_owned()
+}
+
+/// Write the modpack zip to `dest`
+fn write_modpack(
+    dest: &Path,
+    instance: &Path,
+    manifest: &[u8],
+    modlist: &str,
+    overrides: &[PathBuf],
+) -> Result<()> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(fs::File::create(dest)?);
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(manifest)?;
+    zip.start_file("modlist.html", options)?;
+    zip.write_all(modlist.as_bytes())?;
+    for path in overrides {
+        let mut name = String::from("overrides");
+        for component in path.components() {
+            name.push('/');
+            name.push_str(&component.as_os_str().to_string_lossy());
+        }
+        zip.start_file(name, options)?;
+        io::copy(&mut fs::File::open(instance.join(path))?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// List `mods` in the same format as the `modlist.html` in modpacks exported by the CurseForge app
+fn modlist_html(mods: &[Mod]) -> String {
+    let mut html = String::from("<ul>\n");
+    for listed in mods {
+        let _ = write!(
+            html,
+            "<li><a href=\"{}\">{}",
+            escape_html(listed.links.website_url.as_str()),
+            escape_html(&listed.name),
+        );
+        if let Some(author) = listed.authors.first() {
+            let _ = write!(html, " (by {})", escape_html(&author.name));
+        }
+        html.push_str("</a></li>\n");
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,314 @@
+use super::{server::temp_zip, Manifest, ManifestFile, Minecraft};
+use crate::{
+    download::{DownloadManager, PendingDownload},
+    structures::{file_structs::File, ClassId, FileId, ModId},
+    Error, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The name of the file in the instance directory that [`ModpackInstaller`] records the installation in
+pub const INSTALL_RECORD_FILE: &str = "furse-install.json";
+
+/// Read the `manifest.json` of the modpack zip at `path`
+pub fn read_manifest(path: impl AsRef<Path>) -> Result<Manifest> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut bytes = Vec::new();
+    io::copy(&mut archive.by_name("manifest.json")?, &mut bytes)?;
+    Manifest::parse(&bytes)
+}
+
+/// Extract the contents of the `overrides` directory of the modpack zip at `path` into `dest`
+///
+/// Returns the paths of the extracted files relative to `dest`.
+/// Entries whose paths would escape `dest` are skipped.
+pub fn extract_overrides(
+    path: impl AsRef<Path>,
+    overrides: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>> {
+    let (overrides, dest) = (overrides.as_ref(), dest.as_ref());
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut extracted = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|name| Some(name.strip_prefix(overrides).ok()?.to_owned()))
+        else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let out = dest.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(out)?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut fs::File::create(out)?)?;
+        extracted.push(relative);
+    }
+    Ok(extracted)
+}
+
+/// The directory in an instance that files of mods in `class_id` are installed to,
+/// or `None` if files of that class can't be installed into an instance
+///
+/// Mods that couldn't be found are assumed to be mods.
+fn class_dir(class_id: Option<ClassId>) -> Option<&'static str> {
+    match class_id {
+        None | Some(ClassId(6)) => Some("mods"),
+        Some(ClassId(12)) => Some("resourcepacks"),
+        Some(ClassId(17)) => Some("saves"),
+        Some(ClassId(6552)) => Some("shaderpacks"),
+        Some(ClassId(6945)) => Some("datapacks"),
+        Some(_) => None,
+    }
+}
+
+/// A file installed from a modpack
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedFile {
+    pub mod_id: ModId,
+    pub file_id: FileId,
+    /// The path of the file relative to the instance directory
+    pub path: PathBuf,
+    pub required: bool,
+}
+
+/// What was installed into an instance by [`ModpackInstaller`], stored in [`INSTALL_RECORD_FILE`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRecord {
+    pub name: Option<String>,
+    /// The modpack's version
+    pub version: Option<String>,
+    pub minecraft: Minecraft,
+    /// The files that were downloaded
+    pub files: Vec<RecordedFile>,
+    /// The files that still have to be downloaded manually
+    pub pending: Vec<RecordedFile>,
+    /// The files extracted from the overrides, relative to the instance directory
+    pub overrides: Vec<PathBuf>,
+}
+
+impl InstallRecord {
+    /// Load the record from the instance directory `instance`
+    pub fn load(instance: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(
+            instance.as_ref().join(INSTALL_RECORD_FILE),
+        )?)?)
+    }
+
+    /// Store the record in the instance directory `instance`
+    pub fn save(&self, instance: impl AsRef<Path>) -> Result<()> {
+        fs::write(
+            instance.as_ref().join(INSTALL_RECORD_FILE),
+            serde_json::to_vec_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// The outcome of installing a modpack
+#[derive(Debug)]
+pub struct InstallReport {
+    /// The record that was written to the instance directory
+    pub record: InstallRecord,
+    /// Files that have to be downloaded manually, which can be passed on to [`Furse::manual_downloads()`](crate::Furse::manual_downloads)
+    pub blocked: Vec<PendingDownload>,
+    /// Manifest entries whose files don't exist
+    pub missing: Vec<ManifestFile>,
+    /// Optional manifest entries that were not selected
+    pub skipped: Vec<ManifestFile>,
+    /// Files that failed to download
+    pub failed: Vec<(File, Error)>,
+    /// Files of classes that can't be installed into an instance, such as modpacks, which were not downloaded
+    pub unsupported: Vec<File>,
+}
+
+type OptionalCallback = Arc<dyn Fn(&ManifestFile, &File) -> bool + Send + Sync>;
+
+/// Installs CurseForge modpacks into an instance directory
+///
+/// The overrides are extracted, then the files in the manifest are downloaded and verified using a [`DownloadManager`],
+/// whose concurrency, progress reporting, and cancellation apply.
+/// Mods are installed to `mods`, resource packs to `resourcepacks`, shader packs to `shaderpacks`,
+/// data packs to `datapacks`, and worlds to `saves`, where they are left as the archive that was downloaded.
+/// Files of other classes are reported as unsupported.
+/// Finally, an [`InstallRecord`] is written to the instance directory.
+///
+/// ## Example
+/// ```rust,no_run
+/// # use furse::{download::DownloadManager, modpack::ModpackInstaller};
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let installer = ModpackInstaller::new(DownloadManager::new(curseforge))
+///     // Install optional files with "JEI" in their name
+///     .select_optional(|_, file| file.display_name.contains("JEI"));
+/// let report = installer.install("modpack.zip", "instance").await?;
+/// for blocked in report.blocked {
+///     println!("Please download {} from {}", blocked.file.file_name, blocked.website_url);
+/// }
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+#[derive(Clone)]
+pub struct ModpackInstaller {
+    pub(super) manager: DownloadManager,
+    select_optional: OptionalCallback,
+}
+
+impl std::fmt::Debug for ModpackInstaller {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModpackInstaller")
+            .field("manager", &self.manager)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ModpackInstaller {
+    /// Create an installer that downloads files using `manager`, and doesn't install optional files
+    pub fn new(manager: DownloadManager) -> Self {
+        Self {
+            manager,
+            select_optional: Arc::new(|_, _| false),
+        }
+    }
+
+    /// Install the optional manifest entries for which `select` returns `true`
+    pub fn select_optional(
+        mut self,
+        select: impl Fn(&ManifestFile, &File) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.select_optional = Arc::new(select);
+        self
+    }
+
+    /// Download the modpack `file` and install it into `instance`
+    ///
+    /// The modpack zip is downloaded to the system's temporary directory, and removed afterwards.
+    pub async fn install_file(
+        &self,
+        file: &File,
+        instance: impl Into<PathBuf>,
+    ) -> Result<InstallReport> {
+        let zip = temp_zip(file);
+        self.manager.furse().download_file(file, &zip).await?;
+        let report = self.install(zip.clone(), instance).await;
+        tokio::fs::remove_file(zip).await?;
+        report
+    }
+
+    /// Install the modpack zip at `zip` into `instance`
+    pub async fn install(
+        &self,
+        zip: impl Into<PathBuf>,
+        instance: impl Into<PathBuf>,
+    ) -> Result<InstallReport> {
+        let (zip, instance) = (zip.into(), instance.into());
+        let furse = self.manager.furse();
+
+        let manifest = {
+            let zip = zip.clone();
+            tokio::task::spawn_blocking(move || read_manifest(zip))
+                .await
+                .map_err(std::io::Error::other)??
+        };
+        let files = furse.get_manifest_files(&manifest).await?;
+
+        let mut missing = Vec::new();
+        let mut skipped = Vec::new();
+        let mut selected = Vec::new();
+        for (entry, file) in manifest.files.iter().zip(files) {
+            match file {
+                None => missing.push(entry.clone()),
+                Some(file) if entry.required || (self.select_optional)(entry, &file) => {
+                    selected.push((entry, file))
+                }
+                Some(_) => skipped.push(entry.clone()),
+            }
+        }
+
+        let classes = furse
+            .get_mods_by_id(selected.iter().map(|(_, file)| file.mod_id))
+            .await?
+            .into_iter()
+            .map(|(mod_id, found)| (mod_id, found.class_id))
+            .collect::<HashMap<_, _>>();
+
+        let overrides = {
+            let (zip, overrides, instance) =
+                (zip.clone(), manifest.overrides.clone(), instance.clone());
+            tokio::task::spawn_blocking(move || extract_overrides(zip, overrides, instance))
+                .await
+                .map_err(std::io::Error::other)??
+        };
+
+        let mut downloads = Vec::with_capacity(selected.len());
+        let mut recorded = Vec::with_capacity(selected.len());
+        let mut unsupported = Vec::new();
+        for (entry, file) in selected {
+            let Some(dir) = class_dir(classes.get(&file.mod_id).copied().flatten()) else {
+                unsupported.push(file);
+                continue;
+            };
+            tokio::fs::create_dir_all(instance.join(dir)).await?;
+            let path = Path::new(dir).join(&file.file_name);
+            recorded.push(RecordedFile {
+                mod_id: file.mod_id,
+                file_id: file.id,
+                path: path.clone(),
+                required: entry.required,
+            });
+            downloads.push((file, instance.join(path)));
+        }
+        let results = self.manager.download(downloads.clone()).await;
+
+        let mut installed = Vec::new();
+        let mut pending = Vec::new();
+        let mut blocked = Vec::new();
+        let mut failed = Vec::new();
+        for ((result, (file, dest)), recorded) in results.into_iter().zip(downloads).zip(recorded) {
+            match result {
+                Ok(()) => installed.push(recorded),
+                Err(Error::ManualDownloadRequired(manual)) => {
+                    pending.push(recorded);
+                    blocked.push(PendingDownload {
+                        file,
+                        website_url: manual.website_url,
+                        dest,
+                    });
+                }
+                Err(err) => failed.push((file, err)),
+            }
+        }
+
+        let record = InstallRecord {
+            name: manifest.name,
+            version: manifest.version,
+            minecraft: manifest.minecraft,
+            files: installed,
+            pending,
+            overrides,
+        };
+        record.save(&instance)?;
+        Ok(InstallReport {
+            record,
+            blocked,
+            missing,
+            skipped,
+            failed,
+            unsupported,
+        })
+    }
+}
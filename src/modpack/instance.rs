@@ -0,0 +1,242 @@
+use super::{ManifestModLoader, Minecraft};
+use crate::{
+    resolve::Target,
+    structures::{
+        common_structs::ModLoaderType, file_structs::FileReleaseType,
+        fingerprint_structs::IdentifiedMatch, FileId, Fingerprint, JsonMap, ModId, Number,
+    },
+    Furse, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+/// The name of the file describing an instance of the CurseForge app
+pub const INSTANCE_FILE: &str = "minecraftinstance.json";
+
+/// An instance of the CurseForge app, as described by its [`INSTANCE_FILE`]
+///
+/// ## Example
+/// ```rust
+/// # use furse::{modpack::MinecraftInstance, structures::{common_structs::ModLoaderType, FileId, ModId}};
+/// let instance: MinecraftInstance = serde_json::from_str(r#"{
+///     "name": "My Instance",
+///     "gameVersion": "1.20.1",
+///     "baseModLoader": { "name": "forge-47.2.0", "forgeVersion": "47.2.0" },
+///     "installedAddons": [{
+///         "addonID": 238222,
+///         "name": "Just Enough Items (JEI)",
+///         "fileNameOnDisk": "jei-1.20.1-forge-15.2.0.27.jar",
+///         "installedFile": {
+///             "id": 4712866,
+///             "fileName": "jei-1.20.1-forge-15.2.0.27.jar",
+///             "packageFingerprint": 2937396143
+///         }
+///     }]
+/// }"#)?;
+/// assert_eq!(instance.target().loader, ModLoaderType::Forge);
+/// assert_eq!(instance.minecraft().mod_loaders[0].id, "forge-47.2.0");
+/// assert_eq!(instance.pins(), [(ModId(238222), FileId(4712866))]);
+/// # Ok::<_, furse::Error>(())
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MinecraftInstance {
+    pub name: String,
+    /// The game version, e.g. `1.20.1`
+    pub game_version: String,
+    pub base_mod_loader: Option<BaseModLoader>,
+    #[serde(default)]
+    pub installed_addons: Vec<InstalledAddon>,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseModLoader {
+    /// The mod loader and its version separated by a dash, e.g. `forge-47.2.0`
+    pub name: String,
+    /// The mod loader's version, which is set for every mod loader despite its name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forge_version: Option<String>,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl BaseModLoader {
+    /// The mod loader and its version
+    pub fn loader(&self) -> Option<(ModLoaderType, &str)> {
+        let (name, version) = self.name.split_once('-')?;
+        Some((
+            ModLoaderType::from_name(name)?,
+            self.forge_version.as_deref().unwrap_or(version),
+        ))
+    }
+}
+
+/// A mod, resource pack, or other project installed in a [`MinecraftInstance`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledAddon {
+    #[serde(rename = "addonID")]
+    pub addon_id: ModId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The name of the file in the instance, which is different from the file's name when it is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name_on_disk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_file: Option<InstalledAddonFile>,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// The file of an [`InstalledAddon`], in the format of CurseForge's old API
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledAddonFile {
+    pub id: FileId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub file_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_length: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_type: Option<FileReleaseType>,
+    /// The fingerprint of the whole file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_fingerprint: Option<Fingerprint>,
+    /// The game versions, mod loaders, and environments, like [`File.game_versions`](crate::structures::file_structs::File::game_versions)
+    #[serde(default)]
+    pub game_version: Vec<String>,
+    #[serde(default)]
+    pub modules: Vec<AddonModule>,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// A top level file or folder in an [`InstalledAddonFile`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AddonModule {
+    #[serde(rename = "foldername")]
+    pub folder_name: String,
+    pub fingerprint: Fingerprint,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl MinecraftInstance {
+    /// Load the [`INSTANCE_FILE`] of the CurseForge app instance at `instance`
+    pub fn load(instance: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(
+            instance.as_ref().join(INSTANCE_FILE),
+        )?)?)
+    }
+
+    /// The game version and base mod loader, or [`ModLoaderType::Any`] if there is none
+    pub fn target(&self) -> Target {
+        Target::new(
+            self.game_version.clone(),
+            self.base_mod_loader
+                .as_ref()
+                .and_then(BaseModLoader::loader)
+                .map_or(ModLoaderType::Any, |(loader, _)| loader),
+        )
+    }
+
+    /// The game version and base mod loader in the form used by modpack manifests
+    ///
+    /// A base mod loader that isn't recognised is left out.
+    pub fn minecraft(&self) -> Minecraft {
+        Minecraft {
+            version: self.game_version.clone(),
+            mod_loaders: self
+                .base_mod_loader
+                .as_ref()
+                .and_then(BaseModLoader::loader)
+                .map(|(loader, version)| ManifestModLoader {
+                    id: format!("{}-{version}", loader.name().to_lowercase()),
+                    primary: true,
+                    extra: JsonMap::new(),
+                })
+                .into_iter()
+                .collect(),
+            recommended_ram: None,
+            extra: JsonMap::new(),
+        }
+    }
+
+    /// The mod and file IDs of the installed addons, skipping addons without an installed file
+    ///
+    /// These are taken from the instance as is, so nothing has to be fingerprinted.
+    pub fn pins(&self) -> Vec<(ModId, FileId)> {
+        self.installed_addons
+            .iter()
+            .filter_map(|addon| Some((addon.addon_id, addon.installed_file.as_ref()?.id)))
+            .collect()
+    }
+}
+
+impl Furse {
+    /// Get the `File` and `Mod` of each addon in `instance`, using one request for files and one for mods
+    ///
+    /// The results are in the same order as `instance.installed_addons`,
+    /// with `None` in places where the addon has no installed file, or the file could not be found.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::modpack::MinecraftInstance;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let instance = MinecraftInstance::load("Instances/My Instance")?;
+    /// for addon in curseforge.get_instance_addons(&instance).await?.into_iter().flatten() {
+    ///     println!("{}", addon.file.display_name);
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn get_instance_addons(
+        &self,
+        instance: &MinecraftInstance,
+    ) -> Result<Vec<Option<IdentifiedMatch>>> {
+        let pins = instance.pins();
+        if pins.is_empty() {
+            return Ok(vec![None; instance.installed_addons.len()]);
+        }
+        let mut files = self
+            .get_files(pins.iter().map(|(_, file_id)| *file_id).collect())
+            .await?
+            .into_iter()
+            .zip(&pins)
+            .filter_map(|(file, (mod_id, _))| {
+                let file = file.filter(|file| file.mod_id == *mod_id)?;
+                Some((file.id, file))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mods = self
+            .get_mods_by_id(files.values().map(|file| file.mod_id))
+            .await?;
+
+        Ok(instance
+            .installed_addons
+            .iter()
+            .map(|addon| {
+                let file = files.remove(&addon.installed_file.as_ref()?.id)?;
+                let owner = mods.get(&file.mod_id).cloned();
+                Some(IdentifiedMatch {
+                    latest_files: owner
+                        .as_ref()
+                        .map(|owner| owner.latest_files.clone())
+                        .unwrap_or_default(),
+                    owner,
+                    file,
+                })
+            })
+            .collect())
+    }
+}
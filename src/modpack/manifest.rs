@@ -0,0 +1,231 @@
+use crate::{
+    resolve::Target,
+    structures::{common_structs::ModLoaderType, file_structs::File, FileId, JsonMap, ModId},
+    Furse, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    path::{Component, Path},
+};
+
+/// The only manifest type furse understands
+pub const MANIFEST_TYPE: &str = "minecraftModpack";
+
+/// A modpack's `manifest.json`, as found in the root of CurseForge modpack zips
+///
+/// ## Example
+/// ```rust
+/// # use furse::{modpack::Manifest, structures::{common_structs::ModLoaderType, ModId}};
+/// let manifest = Manifest::parse(br#"{
+///     "minecraft": {
+///         "version": "1.20.1",
+///         "modLoaders": [{ "id": "forge-47.2.0", "primary": true }]
+///     },
+///     "manifestType": "minecraftModpack",
+///     "manifestVersion": 1,
+///     "name": "Example Pack",
+///     "version": "1.0.0",
+///     "author": "Someone",
+///     "files": [{ "projectID": 238222, "fileID": 4371807, "required": true }],
+///     "overrides": "overrides"
+/// }"#)?;
+/// assert_eq!(manifest.files[0].project_id, ModId(238222));
+/// assert_eq!(
+///     manifest.primary_loader().unwrap().loader(),
+///     Some((ModLoaderType::Forge, "47.2.0")),
+/// );
+/// # Ok::<_, furse::Error>(())
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub minecraft: Minecraft,
+    /// Should be [`MANIFEST_TYPE`]
+    pub manifest_type: String,
+    /// Should be `1`
+    pub manifest_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The modpack's version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub files: Vec<ManifestFile>,
+    /// The directory in the zip whose contents are copied into the instance
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+fn default_overrides() -> String {
+    "overrides".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Minecraft {
+    /// The game version, e.g. `1.20.1`
+    pub version: String,
+    pub mod_loaders: Vec<ManifestModLoader>,
+    /// The recommended amount of memory in megabytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended_ram: Option<u32>,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestModLoader {
+    /// The mod loader and its version separated by a dash, e.g. `forge-47.2.0`
+    pub id: String,
+    /// Whether this is the mod loader to launch the game with
+    pub primary: bool,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+impl ManifestModLoader {
+    /// Split the ID into the mod loader and its version, e.g. `neoforge-20.4.80-beta` into `NeoForge` and `20.4.80-beta`
+    pub fn loader(&self) -> Option<(ModLoaderType, &str)> {
+        let (name, version) = self.id.split_once('-')?;
+        Some((ModLoaderType::from_name(name)?, version))
+    }
+}
+
+/// A file in the modpack that has to be downloaded from CurseForge
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: ModId,
+    #[serde(rename = "fileID")]
+    pub file_id: FileId,
+    /// Whether the file has to be installed, optional files can be left out by users
+    pub required: bool,
+    /// Fields that furse doesn't model
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// A reason why a [`Manifest`] is invalid
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    #[error("unsupported manifest type `{0}`")]
+    UnsupportedType(String),
+    #[error("unsupported manifest version {0}")]
+    UnsupportedVersion(u32),
+    #[error("unknown mod loader `{0}`")]
+    UnknownLoader(String),
+    #[error("none of the mod loaders is marked as primary")]
+    NoPrimaryLoader,
+    #[error("more than one mod loader is marked as primary")]
+    MultiplePrimaryLoaders,
+    #[error("project {0} is listed more than once")]
+    DuplicateProject(ModId),
+    #[error("the overrides directory `{0}` is not a relative path inside the modpack")]
+    InvalidOverrides(String),
+}
+
+impl Manifest {
+    /// Deserialise and [validate](Manifest::validate) a `manifest.json`
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let manifest: Self = serde_json::from_slice(bytes)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Check that this is a modpack manifest furse understands, with at most one primary mod loader,
+    /// no duplicate projects, and an overrides directory that doesn't escape the modpack
+    pub fn validate(&self) -> std::result::Result<(), ManifestError> {
+        if self.manifest_type != MANIFEST_TYPE {
+            return Err(ManifestError::UnsupportedType(self.manifest_type.clone()));
+        }
+        if self.manifest_version != 1 {
+            return Err(ManifestError::UnsupportedVersion(self.manifest_version));
+        }
+        let mut primaries = 0;
+        for loader in &self.minecraft.mod_loaders {
+            if loader.loader().is_none() {
+                return Err(ManifestError::UnknownLoader(loader.id.clone()));
+            }
+            primaries += usize::from(loader.primary);
+        }
+        match primaries {
+            0 if !self.minecraft.mod_loaders.is_empty() => {
+                return Err(ManifestError::NoPrimaryLoader)
+            }
+            0 | 1 => {}
+            _ => return Err(ManifestError::MultiplePrimaryLoaders),
+        }
+        let mut projects = HashSet::new();
+        for file in &self.files {
+            if !projects.insert(file.project_id) {
+                return Err(ManifestError::DuplicateProject(file.project_id));
+            }
+        }
+        if !Path::new(&self.overrides)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(ManifestError::InvalidOverrides(self.overrides.clone()));
+        }
+        Ok(())
+    }
+
+    /// The mod loader to launch the game with
+    pub fn primary_loader(&self) -> Option<&ManifestModLoader> {
+        self.minecraft
+            .mod_loaders
+            .iter()
+            .find(|loader| loader.primary)
+    }
+
+    /// The game version and primary mod loader, or [`ModLoaderType::Any`] if there is none
+    pub fn target(&self) -> Target {
+        Target::new(
+            self.minecraft.version.clone(),
+            self.primary_loader()
+                .and_then(ManifestModLoader::loader)
+                .map_or(ModLoaderType::Any, |(loader, _)| loader),
+        )
+    }
+}
+
+impl Furse {
+    /// Get the `File`s of all entries in `manifest` using a single request
+    ///
+    /// The results are in the same order as `manifest.files`,
+    /// with `None` in places where the file doesn't exist or doesn't belong to the listed project.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::modpack::Manifest;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// # let bytes = br#"{"minecraft":{"version":"1.18.2","modLoaders":[]},"manifestType":"minecraftModpack","manifestVersion":1,"files":[{"projectID":513688,"fileID":3606078,"required":true}]}"#;
+    /// let manifest = Manifest::parse(bytes)?;
+    /// let files = curseforge.get_manifest_files(&manifest).await?;
+    /// assert!(files.iter().all(Option::is_some));
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn get_manifest_files(&self, manifest: &Manifest) -> Result<Vec<Option<File>>> {
+        if manifest.files.is_empty() {
+            return Ok(Vec::new());
+        }
+        let files = self
+            .get_files(manifest.files.iter().map(|entry| entry.file_id).collect())
+            .await?;
+        Ok(manifest
+            .files
+            .iter()
+            .zip(files)
+            .map(|(entry, file)| file.filter(|file| file.mod_id == entry.project_id))
+            .collect())
+    }
+}
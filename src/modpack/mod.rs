@@ -0,0 +1,21 @@
+//! Reading, installing, and creating CurseForge modpacks, and importing CurseForge app instances
+
+mod export;
+mod install;
+mod instance;
+mod manifest;
+mod server;
+
+pub use export::{ExportOptions, ExportReport};
+pub use install::{
+    extract_overrides, read_manifest, InstallRecord, InstallReport, ModpackInstaller, RecordedFile,
+    INSTALL_RECORD_FILE,
+};
+pub use instance::{
+    AddonModule, BaseModLoader, InstalledAddon, InstalledAddonFile, MinecraftInstance,
+    INSTANCE_FILE,
+};
+pub use manifest::{
+    Manifest, ManifestError, ManifestFile, ManifestModLoader, Minecraft, MANIFEST_TYPE,
+};
+pub use server::ServerInstallReport;
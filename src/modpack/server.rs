@@ -0,0 +1,182 @@
+use super::{extract_overrides, read_manifest, Manifest, Minecraft, ModpackInstaller};
+use crate::{
+    structures::{common_structs::ModLoaderType, file_structs::File, ModId},
+    Error, Furse, Result,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+impl Furse {
+    /// Get the server pack of the client modpack `file`
+    ///
+    /// Returns `file` itself if it is a server pack, and `None` if it doesn't have one.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::structures::ModId;
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// // Get the main file of the All the Mods 9 modpack
+    /// let modpack = curseforge.get_mod(ModId(715572)).await?;
+    /// let client = curseforge.get_mod_file(modpack.id, modpack.main_file_id).await?;
+    /// let server = curseforge.get_server_pack(&client).await?.unwrap();
+    /// assert_eq!(server.is_server_pack, Some(true));
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn get_server_pack(&self, file: &File) -> Result<Option<File>> {
+        if file.is_server_pack == Some(true) {
+            return Ok(Some(file.clone()));
+        }
+        match file.server_pack_file_id {
+            Some(file_id) => Ok(Some(self.get_mod_file(file.mod_id, file_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the client modpack that the server pack `file` belongs to
+    ///
+    /// Returns `file` itself if it is not a server pack, and `None` if its parent isn't known.
+    pub async fn get_server_pack_parent(&self, file: &File) -> Result<Option<File>> {
+        if file.is_server_pack != Some(true) {
+            return Ok(Some(file.clone()));
+        }
+        match file.parent_project_file_id {
+            Some(file_id) => Ok(Some(self.get_mod_file(file.mod_id, file_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the server pack of the main file of the modpack with `mod_id`
+    pub async fn get_latest_server_pack(&self, mod_id: ModId) -> Result<Option<File>> {
+        let modpack = self.get_mod(mod_id).await?;
+        let main_file = self.get_mod_file(mod_id, modpack.main_file_id).await?;
+        self.get_server_pack(&main_file).await
+    }
+}
+
+/// The outcome of installing a server pack
+#[derive(Debug, Clone)]
+pub struct ServerInstallReport {
+    pub server_pack: File,
+    /// The game version and mod loaders of the modpack, if they could be determined
+    pub minecraft: Option<Minecraft>,
+    /// The unpacked files, relative to the server directory
+    pub files: Vec<PathBuf>,
+}
+
+impl ServerInstallReport {
+    /// The mod loader and its version that the server has to be run with
+    pub fn loader(&self) -> Option<(ModLoaderType, &str)> {
+        self.minecraft
+            .as_ref()?
+            .mod_loaders
+            .iter()
+            .find(|loader| loader.primary)?
+            .loader()
+    }
+}
+
+impl ModpackInstaller {
+    /// Download the server pack of the modpack `file` and unpack it into `server`
+    ///
+    /// `file` can be either the client modpack or its server pack. Returns `None` if there is no server pack.
+    /// If all of the server pack's contents are in a single directory, that directory is unpacked instead.
+    /// The game version and mod loader are read from the server pack's `manifest.json`,
+    /// or from the client modpack's if it doesn't have one.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use furse::{download::DownloadManager, modpack::ModpackInstaller, structures::ModId};
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let server_pack = curseforge.get_latest_server_pack(ModId(715572)).await?.unwrap();
+    /// let installer = ModpackInstaller::new(DownloadManager::new(curseforge));
+    /// let report = installer.install_server(&server_pack, "server").await?.unwrap();
+    /// if let Some((loader, version)) = report.loader() {
+    ///     println!("Run the server with {} {version}", loader.name());
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn install_server(
+        &self,
+        file: &File,
+        server: impl Into<PathBuf>,
+    ) -> Result<Option<ServerInstallReport>> {
+        let server = server.into();
+        let furse = self.manager.furse();
+        let Some(server_pack) = furse.get_server_pack(file).await? else {
+            return Ok(None);
+        };
+
+        let zip = temp_zip(&server_pack);
+        furse.download_file(&server_pack, &zip).await?;
+        let unpacked = {
+            let (zip, server) = (zip.clone(), server.clone());
+            tokio::task::spawn_blocking(move || {
+                let root = common_root(&zip)?;
+                let files = extract_overrides(&zip, &root, &server)?;
+                let manifest = if files.iter().any(|file| file == Path::new("manifest.json")) {
+                    Some(Manifest::parse(&fs::read(server.join("manifest.json"))?)?)
+                } else {
+                    None
+                };
+                Ok::<_, Error>((files, manifest))
+            })
+            .await
+        };
+        tokio::fs::remove_file(&zip).await?;
+        let (files, manifest) = unpacked.map_err(io::Error::other)??;
+
+        let minecraft = match manifest {
+            Some(manifest) => Some(manifest.minecraft),
+            None => match furse.get_server_pack_parent(&server_pack).await? {
+                Some(parent) => {
+                    let zip = temp_zip(&parent);
+                    furse.download_file(&parent, &zip).await?;
+                    let manifest = {
+                        let zip = zip.clone();
+                        tokio::task::spawn_blocking(move || read_manifest(zip)).await
+                    };
+                    tokio::fs::remove_file(&zip).await?;
+                    Some(manifest.map_err(io::Error::other)??.minecraft)
+                }
+                None => None,
+            },
+        };
+
+        Ok(Some(ServerInstallReport {
+            server_pack,
+            minecraft,
+            files,
+        }))
+    }
+}
+
+/// Where modpack zips are downloaded to before being unpacked
+pub(super) fn temp_zip(file: &File) -> PathBuf {
+    std::env::temp_dir().join(format!("furse-modpack-{}-{}", file.id, file.file_name))
+}
+
+/// The directory that all entries of the zip at `path` are in, or an empty path if there isn't one
+fn common_root(path: &Path) -> Result<PathBuf> {
+    let archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut root = None;
+    for name in archive.file_names() {
+        let mut components = Path::new(name).components();
+        let Some(first) = components.next() else {
+            continue;
+        };
+        // Files directly in the zip's root mean there is no common directory
+        if components.next().is_none() && !name.ends_with('/') {
+            return Ok(PathBuf::new());
+        }
+        match &root {
+            None => root = Some(first.as_os_str().to_owned()),
+            Some(root) if root == first.as_os_str() => {}
+            Some(_) => return Ok(PathBuf::new()),
+        }
+    }
+    Ok(root.map(PathBuf::from).unwrap_or_default())
+}
@@ -1,4 +1,6 @@
-use crate::{api_calls::Response, Furse, Result};
+//! Sending requests to the CurseForge API and deserialising its responses
+
+use crate::{api_calls::Response, DeserialisationError, Furse, Result};
 use reqwest::{IntoUrl, Url};
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::LazyLock;
@@ -9,16 +11,17 @@ pub(crate) static API_URL_BASE: LazyLock<Url> =
 impl Furse {
     /// Perform a GET request to `url` and deserialise to `T`
     pub(crate) async fn get<T: DeserializeOwned>(&self, url: impl IntoUrl) -> Result<Response<T>> {
+        let url = url.into_url()?;
         let bytes = self
             .client
-            .get(url)
+            .get(url.clone())
             .header("x-api-key", &self.api_key)
             .send()
             .await?
             .error_for_status()?
             .bytes()
             .await?;
-        Ok(serde_json::from_slice(&bytes)?)
+        deserialise(url, &bytes)
     }
 
     /// Perform a POST request to `url` with `body`
@@ -27,9 +30,10 @@ impl Furse {
         url: impl IntoUrl,
         body: &B,
     ) -> Result<Response<T>> {
+        let url = url.into_url()?;
         let bytes = self
             .client
-            .post(url)
+            .post(url.clone())
             .json(body)
             .header("x-api-key", &self.api_key)
             .send()
@@ -37,6 +41,18 @@ impl Furse {
             .error_for_status()?
             .bytes()
             .await?;
-        Ok(serde_json::from_slice(&bytes)?)
+        deserialise(url, &bytes)
     }
 }
+
+/// Deserialise the response `bytes` received from `url`, keeping track of where deserialisation fails
+///
+/// This is what API calls use internally, and can be used to read responses that were stored earlier.
+/// Failures are reported as a [`DeserialisationError`].
+pub fn deserialise<T: DeserializeOwned>(url: Url, bytes: &[u8]) -> Result<T> {
+    let mut de = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut de)
+        .map_err(|err| DeserialisationError::new(url, bytes, err))?;
+    de.end()?;
+    Ok(value)
+}
@@ -0,0 +1,202 @@
+use super::Target;
+use crate::structures::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileRelationType},
+    FileId, ModId,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// Identifies a file involved in a [`Conflict`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    pub mod_id: ModId,
+    pub file_id: FileId,
+    pub display_name: String,
+}
+
+impl From<&File> for ConflictFile {
+    fn from(file: &File) -> Self {
+        Self {
+            mod_id: file.mod_id,
+            file_id: file.id,
+            display_name: file.display_name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ConflictFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.display_name, self.file_id)
+    }
+}
+
+/// A problem with a set of files, found by [`detect_conflicts()`]
+///
+/// The [`Display`](fmt::Display) implementation explains the problem so that it can be shown to users.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// `file` declares that it is incompatible with the mod that `other` belongs to
+    Incompatible {
+        file: ConflictFile,
+        other: ConflictFile,
+    },
+    /// Multiple files belong to the same mod
+    DuplicateMod {
+        mod_id: ModId,
+        files: Vec<ConflictFile>,
+    },
+    /// `file` doesn't support the target's game version
+    GameVersionMismatch {
+        file: ConflictFile,
+        target: String,
+        /// The game versions that `file` supports
+        supported: Vec<String>,
+    },
+    /// `file` doesn't support the target's mod loader
+    LoaderMismatch {
+        file: ConflictFile,
+        target: ModLoaderType,
+        /// The mod loaders that `file` supports
+        supported: Vec<ModLoaderType>,
+    },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Incompatible { file, other } => write!(
+                f,
+                "{file} is marked as incompatible with mod {}, which {other} belongs to",
+                other.mod_id
+            ),
+            Self::DuplicateMod { mod_id, files } => {
+                write!(f, "Multiple files of mod {mod_id} are present: ")?;
+                for (i, file) in files.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{file}")?;
+                }
+                Ok(())
+            }
+            Self::GameVersionMismatch {
+                file,
+                target,
+                supported,
+            } => write!(
+                f,
+                "{file} does not support {target}, only {}",
+                supported.join(", ")
+            ),
+            Self::LoaderMismatch {
+                file,
+                target,
+                supported,
+            } => {
+                write!(f, "{file} does not support {}, only ", target.name())?;
+                for (i, loader) in supported.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(loader.name())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Find problems with installing `files` together, optionally checking them against `target`
+///
+/// This reports files that are marked as [`FileRelationType::Incompatible`] with another mod in the set
+/// (once for each pair of mods, even if both are marked as incompatible with each other),
+/// mods with multiple files, and files that don't support the target's game version or mod loader.
+///
+/// ## Example
+/// ```rust
+/// # use furse::{
+/// #     resolve::{detect_conflicts, Target},
+/// #     structures::{common_structs::ModLoaderType, FileId, ModId},
+/// # };
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// // Get the Terralith mod's v2.0.12 file, which is for 1.18.2
+/// let terralith_file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
+/// let conflicts = detect_conflicts(
+///     &[terralith_file],
+///     Some(&Target::new("1.20.1", ModLoaderType::Fabric)),
+/// );
+/// assert!(!conflicts.is_empty());
+/// for conflict in conflicts {
+///     println!("{conflict}");
+/// }
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+pub fn detect_conflicts(files: &[File], target: Option<&Target>) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+
+    let mut by_mod = BTreeMap::<ModId, Vec<&File>>::new();
+    for file in files {
+        by_mod.entry(file.mod_id).or_default().push(file);
+    }
+
+    // Mutually incompatible mods are reported once per pair
+    let mut incompatible = BTreeSet::new();
+    for file in files {
+        for dependency in &file.dependencies {
+            if dependency.relation_type != FileRelationType::Incompatible {
+                continue;
+            }
+            let pair = if file.mod_id < dependency.mod_id {
+                (file.mod_id, dependency.mod_id)
+            } else {
+                (dependency.mod_id, file.mod_id)
+            };
+            let Some(others) = by_mod.get(&dependency.mod_id) else {
+                continue;
+            };
+            if !incompatible.insert(pair) {
+                continue;
+            }
+            for other in others {
+                conflicts.push(Conflict::Incompatible {
+                    file: file.into(),
+                    other: (*other).into(),
+                });
+            }
+        }
+    }
+
+    for (mod_id, mod_files) in &by_mod {
+        if mod_files.len() > 1 {
+            conflicts.push(Conflict::DuplicateMod {
+                mod_id: *mod_id,
+                files: mod_files.iter().map(|file| (*file).into()).collect(),
+            });
+        }
+    }
+
+    if let Some(target) = target {
+        for file in files {
+            if !target.supports_game_version(file) {
+                conflicts.push(Conflict::GameVersionMismatch {
+                    file: file.into(),
+                    target: target.game_version.clone(),
+                    supported: file.compatibility().game_versions,
+                });
+            }
+            if !target.supports_loader(file) {
+                conflicts.push(Conflict::LoaderMismatch {
+                    file: file.into(),
+                    target: target.loader,
+                    supported: file.compatibility().loaders,
+                });
+            }
+        }
+    }
+
+    conflicts
+}
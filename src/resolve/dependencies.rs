@@ -0,0 +1,274 @@
+use super::FileSelector;
+use crate::{
+    structures::{
+        file_structs::{File, FileRelationType},
+        ModId,
+    },
+    Furse, Result,
+};
+use std::collections::{HashMap, HashSet};
+
+/// A mod or file to resolve the dependencies of
+#[derive(Debug, Clone)]
+pub enum ResolveRoot {
+    /// A mod, for which a compatible file is picked
+    Mod(ModId),
+    /// A specific file
+    File(Box<File>),
+}
+
+impl From<ModId> for ResolveRoot {
+    fn from(mod_id: ModId) -> Self {
+        Self::Mod(mod_id)
+    }
+}
+
+impl From<File> for ResolveRoot {
+    fn from(file: File) -> Self {
+        Self::File(Box::new(file))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveOptions {
+    /// The policy used to pick a file for each mod
+    pub selector: FileSelector,
+    /// Whether to also install optional dependencies
+    pub include_optional: bool,
+}
+
+/// Why an entry is part of an [`InstallPlan`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// It was one of the roots requested
+    Root,
+    /// It is a dependency of the mod with ID `of`
+    Dependency {
+        of: ModId,
+        relation: FileRelationType,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanEntry {
+    pub file: File,
+    pub reason: Reason,
+}
+
+/// A mod that doesn't exist, or for which no compatible file could be found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unresolved {
+    pub mod_id: ModId,
+    pub reason: Reason,
+}
+
+/// The files to install, as resolved by [`Furse::resolve_dependencies()`]
+#[derive(Debug, Clone, Default)]
+pub struct InstallPlan {
+    /// The files to install, with roots first, followed by dependencies in breadth-first order
+    pub entries: Vec<PlanEntry>,
+    /// Mods that don't exist, or for which no compatible file was found
+    pub unresolved: Vec<Unresolved>,
+    /// Dependency cycles that were found, each starting and ending with the same mod
+    ///
+    /// One cycle is reported for each dependency that leads back to a mod on the path of a depth-first search,
+    /// so cycles through mods that were already searched from another path are not reported.
+    pub cycles: Vec<Vec<ModId>>,
+}
+
+impl Furse {
+    /// Recursively resolve the dependencies of `roots`, picking files using the selector in `options`
+    ///
+    /// Required dependencies are always followed, and optional dependencies if enabled in `options`.
+    /// Embedded libraries, included mods, tools, and incompatibilities are not followed.
+    /// Mods are fetched in batches, one per level of dependencies.
+    /// Files are picked from a mod's `latest_files_indexes` where possible,
+    /// falling back to searching all of its files if there is no suitable index,
+    /// or if the indexed file is missing or rejected by the selector once fetched.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::{
+    /// #     resolve::{FileSelector, ResolveOptions, Target},
+    /// #     structures::{common_structs::ModLoaderType, ModId},
+    /// # };
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let options = ResolveOptions {
+    ///     selector: FileSelector::new(Target::new("1.20.1", ModLoaderType::Fabric)),
+    ///     include_optional: false,
+    /// };
+    /// // Resolve Sodium Extra, which depends on Sodium
+    /// let plan = curseforge
+    ///     .resolve_dependencies(vec![ModId(447673).into()], &options)
+    ///     .await?;
+    /// assert!(plan.entries.iter().any(|entry| entry.file.mod_id == ModId(394468)));
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn resolve_dependencies(
+        &self,
+        roots: Vec<ResolveRoot>,
+        options: &ResolveOptions,
+    ) -> Result<InstallPlan> {
+        let mut plan = InstallPlan::default();
+        let mut seen = HashSet::new();
+        // The dependencies followed from each mod
+        let mut edges = HashMap::<ModId, Vec<ModId>>::new();
+        let mut resolved = Vec::new();
+        let mut queue = Vec::new();
+
+        for root in roots {
+            match root {
+                ResolveRoot::File(file) => {
+                    if seen.insert(file.mod_id) {
+                        resolved.push((*file, Reason::Root));
+                    }
+                }
+                ResolveRoot::Mod(mod_id) => {
+                    if seen.insert(mod_id) {
+                        queue.push((mod_id, Reason::Root));
+                    }
+                }
+            }
+        }
+
+        loop {
+            let (files, unresolved) = self.pick_files(&queue, &options.selector).await?;
+            resolved.extend(files);
+            plan.unresolved.extend(unresolved);
+            queue.clear();
+            if resolved.is_empty() {
+                break;
+            }
+
+            for (file, reason) in resolved.drain(..) {
+                for dependency in &file.dependencies {
+                    let follow = match dependency.relation_type {
+                        FileRelationType::RequiredDependency => true,
+                        FileRelationType::OptionalDependency => options.include_optional,
+                        _ => false,
+                    };
+                    if !follow {
+                        continue;
+                    }
+                    let followed = edges.entry(file.mod_id).or_default();
+                    if !followed.contains(&dependency.mod_id) {
+                        followed.push(dependency.mod_id);
+                    }
+                    if seen.insert(dependency.mod_id) {
+                        queue.push((
+                            dependency.mod_id,
+                            Reason::Dependency {
+                                of: file.mod_id,
+                                relation: dependency.relation_type,
+                            },
+                        ));
+                    }
+                }
+                plan.entries.push(PlanEntry { file, reason });
+            }
+        }
+
+        plan.cycles = find_cycles(&edges, plan.entries.iter().map(|entry| entry.file.mod_id));
+        Ok(plan)
+    }
+
+    /// Pick a file using `selector` for each mod in `queue`
+    async fn pick_files(
+        &self,
+        queue: &[(ModId, Reason)],
+        selector: &FileSelector,
+    ) -> Result<(Vec<(File, Reason)>, Vec<Unresolved>)> {
+        let mut picked = Vec::new();
+        let mut unresolved = Vec::new();
+        if queue.is_empty() {
+            return Ok((picked, unresolved));
+        }
+
+        let mods = self
+            .get_mods_by_id(queue.iter().map(|(mod_id, _)| *mod_id))
+            .await?;
+        // Mods that don't exist are unresolved without searching for their files
+        let mut latest = self.select_latest(&mods, selector).await?;
+        for &(mod_id, reason) in queue {
+            match latest.remove(&mod_id).flatten() {
+                Some(file) => picked.push((file, reason)),
+                None => unresolved.push(Unresolved { mod_id, reason }),
+            }
+        }
+
+        Ok((picked, unresolved))
+    }
+}
+
+/// Find the cycles in the dependency graph `edges` using a depth-first search from each mod in `order`
+///
+/// One cycle is reported per back-edge, i.e. for each dependency that leads back to a mod on the current search path.
+/// This is neither every cycle in the graph, nor a minimal set of cycles whose breaking makes the graph acyclic.
+fn find_cycles(
+    edges: &HashMap<ModId, Vec<ModId>>,
+    order: impl IntoIterator<Item = ModId>,
+) -> Vec<Vec<ModId>> {
+    fn visit(
+        mod_id: ModId,
+        edges: &HashMap<ModId, Vec<ModId>>,
+        visited: &mut HashSet<ModId>,
+        path: &mut Vec<ModId>,
+        cycles: &mut Vec<Vec<ModId>>,
+    ) {
+        visited.insert(mod_id);
+        path.push(mod_id);
+        for &dependency in edges.get(&mod_id).into_iter().flatten() {
+            if let Some(start) = path.iter().position(|&id| id == dependency) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(dependency);
+                cycles.push(cycle);
+            } else if !visited.contains(&dependency) {
+                visit(dependency, edges, visited, path, cycles);
+            }
+        }
+        path.pop();
+    }
+
+    let mut visited = HashSet::new();
+    let mut cycles = Vec::new();
+    for mod_id in order {
+        if !visited.contains(&mod_id) {
+            visit(mod_id, edges, &mut visited, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_cycles() {
+        // 1 -> 2 -> 1 and 1 -> 2 -> 3 -> 1 share the dependency of 1 on 2
+        let edges = HashMap::from([
+            (ModId(1), vec![ModId(2)]),
+            (ModId(2), vec![ModId(1), ModId(3)]),
+            (ModId(3), vec![ModId(1)]),
+        ]);
+        assert_eq!(
+            find_cycles(&edges, [ModId(1), ModId(2), ModId(3)]),
+            [
+                vec![ModId(1), ModId(2), ModId(1)],
+                vec![ModId(1), ModId(2), ModId(3), ModId(1)],
+            ]
+        );
+
+        // 1 -> 3 -> 1 is not reported, since 3 was already searched through 2
+        let edges = HashMap::from([
+            (ModId(1), vec![ModId(2), ModId(3)]),
+            (ModId(2), vec![ModId(3)]),
+            (ModId(3), vec![ModId(1)]),
+        ]);
+        assert_eq!(
+            find_cycles(&edges, [ModId(1)]),
+            [vec![ModId(1), ModId(2), ModId(3), ModId(1)]]
+        );
+    }
+}
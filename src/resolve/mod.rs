@@ -0,0 +1,65 @@
+//! Working out which files to install
+
+mod conflicts;
+mod dependencies;
+mod selector;
+mod updates;
+
+pub use conflicts::{detect_conflicts, Conflict, ConflictFile};
+pub use dependencies::{InstallPlan, PlanEntry, Reason, ResolveOptions, ResolveRoot, Unresolved};
+pub use selector::{FileSelector, Rejection, Selection, VersionMatching};
+pub use updates::{FileChangelog, InstalledFile, UpdateCheck};
+
+use crate::structures::{
+    common_structs::ModLoaderType,
+    file_structs::{File, FileIndex},
+};
+
+/// The game version and mod loader of an instance that files are installed to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    /// The game version, e.g. `1.20.1`
+    pub game_version: String,
+    /// The mod loader, or [`ModLoaderType::Any`] to accept files for any mod loader
+    pub loader: ModLoaderType,
+}
+
+impl Target {
+    pub fn new(game_version: impl Into<String>, loader: ModLoaderType) -> Self {
+        Self {
+            game_version: game_version.into(),
+            loader,
+        }
+    }
+
+    /// Check whether `file` supports this game version and mod loader
+    pub fn is_compatible(&self, file: &File) -> bool {
+        self.supports_game_version(file) && self.supports_loader(file)
+    }
+
+    /// Check whether `file` supports this game version
+    pub fn supports_game_version(&self, file: &File) -> bool {
+        file.compatibility()
+            .game_versions
+            .contains(&self.game_version)
+    }
+
+    /// Check whether `file` supports this mod loader
+    ///
+    /// Files that don't list any mod loader, such as resource packs, are considered compatible with every mod loader.
+    pub fn supports_loader(&self, file: &File) -> bool {
+        let compatibility = file.compatibility();
+        self.loader == ModLoaderType::Any
+            || (compatibility.loaders.is_empty() && compatibility.unrecognised_loaders.is_empty())
+            || compatibility.loaders.contains(&self.loader)
+    }
+
+    /// Check whether the file indexed by `index` supports this game version and mod loader
+    pub fn is_compatible_index(&self, index: &FileIndex) -> bool {
+        index.game_version == self.game_version
+            && (self.loader == ModLoaderType::Any
+                || index
+                    .mod_loader
+                    .is_none_or(|loader| loader == ModLoaderType::Any || loader == self.loader))
+    }
+}
@@ -0,0 +1,248 @@
+use super::Target;
+use crate::{
+    game_version::GameVersionReq,
+    structures::{
+        common_structs::ModLoaderType,
+        file_structs::{File, FileIndex, FileReleaseType},
+    },
+};
+use std::{cmp::Reverse, fmt};
+
+/// How a file's game versions are matched against the target's game version
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VersionMatching {
+    /// The file has to list the target's game version
+    #[default]
+    Exact,
+    /// The file has to list a game version with the same major and minor version,
+    /// e.g. `1.20.4` matches a target of `1.20.1`
+    MinorFamily,
+    /// The file has to list a game version in this range, ignoring the target's game version
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::{
+    /// #     resolve::{FileSelector, Target, VersionMatching},
+    /// #     structures::common_structs::ModLoaderType,
+    /// # };
+    /// let mut selector = FileSelector::new(Target::new("1.20.1", ModLoaderType::Fabric));
+    /// selector.version_matching = VersionMatching::Range(">=1.19.2, <1.21".parse()?);
+    /// # Ok::<_, furse::game_version::GameVersionParseError>(())
+    /// ```
+    Range(GameVersionReq),
+}
+
+/// Why a file was rejected by a [`FileSelector`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The file is not available for download
+    Unavailable,
+    /// The file is early access content
+    EarlyAccess,
+    /// The file is a server pack
+    ServerPack,
+    /// The file is less stable than allowed
+    ReleaseType(FileReleaseType),
+    /// The file doesn't support the target's game version, only these
+    GameVersion(Vec<String>),
+    /// The file doesn't support the target's mod loader, only these
+    Loader(Vec<ModLoaderType>),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => f.write_str("the file is not available"),
+            Self::EarlyAccess => f.write_str("the file is early access content"),
+            Self::ServerPack => f.write_str("the file is a server pack"),
+            Self::ReleaseType(release_type) => {
+                write!(
+                    f,
+                    "the file is a {release_type:?} release, which is not allowed"
+                )
+            }
+            Self::GameVersion(supported) => write!(
+                f,
+                "the file does not support the game version, only {}",
+                supported.join(", ")
+            ),
+            Self::Loader(supported) => {
+                f.write_str("the file does not support the mod loader, only ")?;
+                for (i, loader) in supported.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(loader.name())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The result of [`FileSelector::select()`]
+#[derive(Debug, Clone)]
+pub struct Selection<'a, T> {
+    /// The best file, if any were accepted
+    pub selected: Option<&'a T>,
+    /// The files that were rejected, and why
+    pub rejected: Vec<(&'a T, Rejection)>,
+}
+
+/// A configurable policy for choosing which file of a mod to install
+///
+/// By default, files have to be available, not early access content, not server packs,
+/// and support the target exactly. Releases are preferred over betas and alphas,
+/// with newer files preferred among files of the same release type.
+///
+/// ## Example
+/// ```rust
+/// # use furse::{
+/// #     resolve::{FileSelector, Target},
+/// #     structures::{common_structs::ModLoaderType, FileId, ModId},
+/// # };
+/// # tokio_test::block_on(async {
+/// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+/// let selector = FileSelector::new(Target::new("1.18.2", ModLoaderType::Fabric));
+/// let files = curseforge.get_mod_files(ModId(513688)).await?;
+/// let selection = selector.select(&files);
+/// assert!(selection.selected.is_some());
+/// for (file, rejection) in selection.rejected {
+///     println!("Skipped {} because {rejection}", file.display_name);
+/// }
+/// # Ok::<_, furse::Error>(()) }).unwrap()
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSelector {
+    pub target: Target,
+    pub version_matching: VersionMatching,
+    /// The least stable release type allowed, e.g. [`FileReleaseType::Beta`] allows releases and betas
+    pub min_release_type: FileReleaseType,
+    /// Whether to prefer more stable files over newer ones
+    pub prefer_stable: bool,
+    pub allow_early_access: bool,
+    pub allow_unavailable: bool,
+    pub allow_server_packs: bool,
+}
+
+impl FileSelector {
+    /// Create a selector with the default policy for `target`
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            version_matching: VersionMatching::Exact,
+            min_release_type: FileReleaseType::Alpha,
+            prefer_stable: true,
+            allow_early_access: false,
+            allow_unavailable: false,
+            allow_server_packs: false,
+        }
+    }
+
+    /// Check whether `file` is acceptable
+    pub fn check(&self, file: &File) -> Result<(), Rejection> {
+        if !self.allow_unavailable && !file.is_available {
+            return Err(Rejection::Unavailable);
+        }
+        if !self.allow_early_access && file.is_early_access_content == Some(true) {
+            return Err(Rejection::EarlyAccess);
+        }
+        if !self.allow_server_packs && file.is_server_pack == Some(true) {
+            return Err(Rejection::ServerPack);
+        }
+        if file.release_type > self.min_release_type {
+            return Err(Rejection::ReleaseType(file.release_type));
+        }
+        let compatibility = file.compatibility();
+        if !compatibility
+            .game_versions
+            .iter()
+            .any(|version| self.matches_game_version(version))
+        {
+            return Err(Rejection::GameVersion(compatibility.game_versions));
+        }
+        if !self.target.supports_loader(file) {
+            return Err(Rejection::Loader(compatibility.loaders));
+        }
+        Ok(())
+    }
+
+    /// Check whether the file indexed by `index` is acceptable
+    ///
+    /// Indexes don't contain availability, early access, or server pack information, so these are not checked.
+    pub fn check_index(&self, index: &FileIndex) -> Result<(), Rejection> {
+        if index.release_type > self.min_release_type {
+            return Err(Rejection::ReleaseType(index.release_type));
+        }
+        if !self.matches_game_version(&index.game_version) {
+            return Err(Rejection::GameVersion(vec![index.game_version.clone()]));
+        }
+        match index.mod_loader {
+            Some(loader)
+                if self.target.loader != ModLoaderType::Any
+                    && loader != ModLoaderType::Any
+                    && loader != self.target.loader =>
+            {
+                Err(Rejection::Loader(vec![loader]))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Choose the best of `files`, explaining why the others were rejected
+    pub fn select<'a>(&self, files: &'a [File]) -> Selection<'a, File> {
+        let mut rejected = Vec::new();
+        let mut accepted = Vec::new();
+        for file in files {
+            match self.check(file) {
+                Ok(()) => accepted.push(file),
+                Err(rejection) => rejected.push((file, rejection)),
+            }
+        }
+        let selected = if self.prefer_stable {
+            accepted
+                .into_iter()
+                .max_by_key(|file| (Reverse(file.release_type), file.file_date))
+        } else {
+            accepted.into_iter().max_by_key(|file| file.file_date)
+        };
+        Selection { selected, rejected }
+    }
+
+    /// Choose the best of `indexes`, explaining why the others were rejected
+    ///
+    /// Indexes don't contain dates, so newer files are determined by their higher IDs.
+    pub fn select_index<'a>(&self, indexes: &'a [FileIndex]) -> Selection<'a, FileIndex> {
+        let mut rejected = Vec::new();
+        let mut accepted = Vec::new();
+        for index in indexes {
+            match self.check_index(index) {
+                Ok(()) => accepted.push(index),
+                Err(rejection) => rejected.push((index, rejection)),
+            }
+        }
+        let selected = if self.prefer_stable {
+            accepted
+                .into_iter()
+                .max_by_key(|index| (Reverse(index.release_type), index.file_id))
+        } else {
+            accepted.into_iter().max_by_key(|index| index.file_id)
+        };
+        Selection { selected, rejected }
+    }
+
+    fn matches_game_version(&self, version: &str) -> bool {
+        match &self.version_matching {
+            VersionMatching::Exact => version == self.target.game_version,
+            VersionMatching::MinorFamily => {
+                let family = |version: &str| {
+                    let mut parts = version.split('.');
+                    Some((parts.next()?.to_owned(), parts.next()?.to_owned()))
+                };
+                family(version)
+                    .is_some_and(|family_of| Some(family_of) == family(&self.target.game_version))
+            }
+            VersionMatching::Range(range) => range.matches_str(version),
+        }
+    }
+}
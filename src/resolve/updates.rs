@@ -0,0 +1,291 @@
+use super::FileSelector;
+use crate::{
+    structures::{
+        file_structs::{File, FileDependency},
+        mod_structs::Mod,
+        FileId, Fingerprint, ModId,
+    },
+    Error, Furse, Result,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+/// The number of changelogs fetched at the same time by [`Furse::changelogs_between()`],
+/// and of mods whose files are searched at the same time when picking the newest files
+const CHANGELOG_CONCURRENCY: usize = 8;
+
+/// An installed file to check for updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstalledFile {
+    /// A file whose mod and file IDs are known
+    Known { mod_id: ModId, file_id: FileId },
+    /// A file that is identified by its fingerprint
+    Fingerprint(Fingerprint),
+}
+
+/// The result of checking an installed file for updates
+#[derive(Debug, Clone)]
+pub struct UpdateCheck {
+    pub mod_id: ModId,
+    /// The installed file
+    pub current: File,
+    /// The newest compatible file, if it is newer than the installed file
+    pub update: Option<File>,
+}
+
+impl UpdateCheck {
+    /// The dependencies that the update adds, and the ones that it removes
+    ///
+    /// Dependencies whose relation type changed are included in both.
+    pub fn dependency_changes(&self) -> (Vec<FileDependency>, Vec<FileDependency>) {
+        let Some(update) = &self.update else {
+            return (Vec::new(), Vec::new());
+        };
+        let contains = |dependencies: &[FileDependency], dependency: &FileDependency| {
+            dependencies.iter().any(|other| {
+                other.mod_id == dependency.mod_id && other.relation_type == dependency.relation_type
+            })
+        };
+        let added = update
+            .dependencies
+            .iter()
+            .filter(|dependency| !contains(&self.current.dependencies, dependency))
+            .copied()
+            .collect();
+        let removed = self
+            .current
+            .dependencies
+            .iter()
+            .filter(|dependency| !contains(&update.dependencies, dependency))
+            .copied()
+            .collect();
+        (added, removed)
+    }
+
+    /// Whether the update adds or removes any dependencies
+    pub fn changes_dependencies(&self) -> bool {
+        let (added, removed) = self.dependency_changes();
+        !added.is_empty() || !removed.is_empty()
+    }
+}
+
+/// A file and its changelog, as returned by [`Furse::changelogs_between()`]
+#[derive(Debug, Clone)]
+pub struct FileChangelog {
+    pub file: File,
+    /// The changelog in HTML
+    pub changelog: String,
+}
+
+impl Furse {
+    /// Get the changelogs of the files of the mod with `mod_id` released after `from_file_id`, up to and including `to_file_id`
+    ///
+    /// Only intermediate files accepted by `filter` are included, so that the changelogs are those of
+    /// the same game version and mod loader line. The file with `to_file_id` is always included.
+    /// Changelogs are fetched a few at a time, and returned from oldest to newest.
+    /// Nothing is returned if `from_file_id` is not older than `to_file_id`.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::{
+    /// #     resolve::{FileSelector, Target},
+    /// #     structures::{common_structs::ModLoaderType, FileId, ModId},
+    /// # };
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let filter = FileSelector::new(Target::new("1.18.2", ModLoaderType::Fabric));
+    /// // Get the Terralith mod's changelogs since v2.0.12
+    /// let from = FileId(3606078);
+    /// let to = curseforge.get_mod(ModId(513688)).await?.main_file_id;
+    /// for entry in curseforge.changelogs_between(ModId(513688), from, to, &filter).await? {
+    ///     println!("{}:\n{}", entry.file.display_name, entry.changelog);
+    /// }
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn changelogs_between(
+        &self,
+        mod_id: ModId,
+        from_file_id: FileId,
+        to_file_id: FileId,
+        filter: &FileSelector,
+    ) -> Result<Vec<FileChangelog>> {
+        let files = self.get_mod_files(mod_id).await?;
+        let find = |file_id| files.iter().find(|file| file.id == file_id).cloned();
+        let from = match find(from_file_id) {
+            Some(file) => file,
+            None => self.get_mod_file(mod_id, from_file_id).await?,
+        };
+        let to = match find(to_file_id) {
+            Some(file) => file,
+            None => self.get_mod_file(mod_id, to_file_id).await?,
+        };
+        if from.file_date >= to.file_date {
+            return Ok(Vec::new());
+        }
+
+        let mut between = files
+            .into_iter()
+            .filter(|file| {
+                file.id != to.id
+                    && file.file_date > from.file_date
+                    && file.file_date < to.file_date
+                    && filter.check(file).is_ok()
+            })
+            .collect::<Vec<_>>();
+        between.push(to);
+        between.sort_by_key(|file| (file.file_date, file.id));
+
+        stream::iter(between)
+            .map(|file| async move {
+                let changelog = self.get_mod_file_changelog(mod_id, file.id).await?;
+                Ok(FileChangelog { file, changelog })
+            })
+            .buffered(CHANGELOG_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Check the `installed` files for updates, picking the newest file accepted by `selector`
+    ///
+    /// The results are in the same order as `installed`, with `None` in places where the installed file could not be found.
+    /// Files are picked from the mod's `latest_files_indexes` where possible,
+    /// falling back to searching all of its files if there is no suitable index,
+    /// or if the indexed file is missing or rejected by the selector once fetched.
+    /// Use a selector with `prefer_stable` disabled to update to the newest file regardless of its release type.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::{
+    /// #     resolve::{FileSelector, InstalledFile, Target},
+    /// #     structures::{common_structs::ModLoaderType, file_structs::FileReleaseType, FileId, ModId},
+    /// # };
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// let mut selector = FileSelector::new(Target::new("1.18.2", ModLoaderType::Fabric));
+    /// selector.min_release_type = FileReleaseType::Release;
+    /// // Check the Terralith mod's v2.0.12 file for updates
+    /// let installed = InstalledFile::Known {
+    ///     mod_id: ModId(513688),
+    ///     file_id: FileId(3606078),
+    /// };
+    /// let checks = curseforge.check_updates(vec![installed], &selector).await?;
+    /// // There have been newer versions for 1.18.2
+    /// assert!(checks[0].as_ref().unwrap().update.is_some());
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub async fn check_updates(
+        &self,
+        installed: Vec<InstalledFile>,
+        selector: &FileSelector,
+    ) -> Result<Vec<Option<UpdateCheck>>> {
+        let mut file_ids = Vec::new();
+        let mut fingerprints = Vec::new();
+        for file in &installed {
+            match *file {
+                InstalledFile::Known { file_id, .. } => file_ids.push(file_id),
+                InstalledFile::Fingerprint(fingerprint) => fingerprints.push(fingerprint),
+            }
+        }
+
+        let mut current = HashMap::new();
+        if !file_ids.is_empty() {
+            for file in self.get_files(file_ids).await?.into_iter().flatten() {
+                current.insert(file.id, file);
+            }
+        }
+        let mut by_fingerprint = HashMap::new();
+        if !fingerprints.is_empty() {
+            for found in self
+                .get_fingerprint_matches(fingerprints)
+                .await?
+                .exact_matches
+            {
+                by_fingerprint.insert(found.file.file_fingerprint, found.file);
+            }
+        }
+        let current = installed
+            .into_iter()
+            .map(|file| match file {
+                InstalledFile::Known { mod_id, file_id } => current
+                    .get(&file_id)
+                    .filter(|file| file.mod_id == mod_id)
+                    .cloned(),
+                InstalledFile::Fingerprint(fingerprint) => {
+                    by_fingerprint.get(&fingerprint).cloned()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mods = self
+            .get_mods_by_id(current.iter().flatten().map(|file| file.mod_id))
+            .await?;
+        let latest = self.select_latest(&mods, selector).await?;
+
+        Ok(current
+            .into_iter()
+            .map(|current| {
+                let current = current?;
+                let update = latest
+                    .get(&current.mod_id)
+                    .cloned()
+                    .flatten()
+                    .filter(|newest| {
+                        newest.id != current.id && newest.file_date > current.file_date
+                    });
+                Some(UpdateCheck {
+                    mod_id: current.mod_id,
+                    current,
+                    update,
+                })
+            })
+            .collect())
+    }
+
+    /// Pick the newest file accepted by `selector` for each of `mods`
+    ///
+    /// Files are picked from the mod's `latest_files_indexes` where possible,
+    /// falling back to searching all of its files if there is no suitable index,
+    /// or if the indexed file is missing or rejected by the selector once fetched.
+    /// The fallback searches are run a few at a time.
+    pub(super) async fn select_latest(
+        &self,
+        mods: &HashMap<ModId, Mod>,
+        selector: &FileSelector,
+    ) -> Result<HashMap<ModId, Option<File>>> {
+        let mut latest = HashMap::with_capacity(mods.len());
+        let mut indexed = Vec::new();
+        let mut unindexed = Vec::new();
+        for (&mod_id, found) in mods {
+            match selector.select_index(&found.latest_files_indexes).selected {
+                Some(index) => indexed.push((mod_id, index.file_id)),
+                None => unindexed.push(mod_id),
+            }
+        }
+
+        if !indexed.is_empty() {
+            let files = self
+                .get_files(indexed.iter().map(|(_, file_id)| *file_id).collect())
+                .await?;
+            for ((mod_id, _), file) in indexed.into_iter().zip(files) {
+                // Indexes lack some of the information the selector checks
+                match file.filter(|file| selector.check(file).is_ok()) {
+                    Some(file) => {
+                        latest.insert(mod_id, Some(file));
+                    }
+                    None => unindexed.push(mod_id),
+                }
+            }
+        }
+
+        let searched = stream::iter(unindexed)
+            .map(|mod_id| async move {
+                let files = self.get_mod_files(mod_id).await?;
+                Ok::<_, Error>((mod_id, selector.select(&files).selected.cloned()))
+            })
+            .buffer_unordered(CHANGELOG_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+        latest.extend(searched);
+        Ok(latest)
+    }
+}
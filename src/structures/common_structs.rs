@@ -3,8 +3,8 @@ use super::*;
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Category {
-    pub id: ID,
-    pub game_id: ID,
+    pub id: CategoryId,
+    pub game_id: GameId,
     pub name: String,
     pub slug: String,
     pub url: Url,
@@ -13,9 +13,12 @@ pub struct Category {
     /// Whether this is a top level category for other categories
     pub is_class: Option<bool>,
     /// The ID of the class which this category is under
-    pub class_id: Option<ID>,
-    pub parent_category_id: Option<ID>,
+    pub class_id: Option<ClassId>,
+    pub parent_category_id: Option<CategoryId>,
     pub display_index: Option<Number>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -29,6 +32,9 @@ pub struct SortableGameVersion {
     pub game_version: String,
     pub game_version_release_date: UtcTime,
     pub game_version_type_id: Option<ID>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
@@ -44,7 +50,7 @@ pub struct Pagination {
     pub total_count: Number,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum ModLoaderType {
     Any = 0,
@@ -55,3 +61,33 @@ pub enum ModLoaderType {
     Quilt = 5,
     NeoForge = 6,
 }
+
+impl ModLoaderType {
+    /// The name of the mod loader as it appears in [`File.game_versions`](super::file_structs::File::game_versions)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Any => "Any",
+            Self::Forge => "Forge",
+            Self::Cauldron => "Cauldron",
+            Self::LiteLoader => "LiteLoader",
+            Self::Fabric => "Fabric",
+            Self::Quilt => "Quilt",
+            Self::NeoForge => "NeoForge",
+        }
+    }
+
+    /// Parse a mod loader name as it appears in [`File.game_versions`](super::file_structs::File::game_versions),
+    /// ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Forge,
+            Self::Cauldron,
+            Self::LiteLoader,
+            Self::Fabric,
+            Self::Quilt,
+            Self::NeoForge,
+        ]
+        .into_iter()
+        .find(|loader| loader.name().eq_ignore_ascii_case(name))
+    }
+}
@@ -1,12 +1,13 @@
 use super::*;
+use crate::game_version::GameVersion;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
-    pub id: ID,
+    pub id: FileId,
     /// The game ID of the mod that this file belongs to
-    pub game_id: ID,
-    pub mod_id: ID,
+    pub game_id: GameId,
+    pub mod_id: ModId,
     pub is_available: bool,
     pub display_name: String,
     /// Exact file name
@@ -27,17 +28,139 @@ pub struct File {
     pub sortable_game_versions: Vec<SortableGameVersion>,
     pub dependencies: Vec<FileDependency>,
     pub expose_as_alternative: Option<bool>,
-    pub parent_project_file_id: Option<ID>,
-    pub alternate_file_id: Option<ID>,
+    pub parent_project_file_id: Option<FileId>,
+    pub alternate_file_id: Option<FileId>,
     pub is_server_pack: Option<bool>,
-    pub server_pack_file_id: Option<ID>,
+    pub server_pack_file_id: Option<FileId>,
     pub is_early_access_content: Option<bool>,
     pub early_access_end_date: Option<UtcTime>,
-    pub file_fingerprint: Number,
+    pub file_fingerprint: Fingerprint,
     pub modules: Option<Vec<FileModule>>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
-#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+/// The `game_version_type_id` of mod loaders in [`File.sortable_game_versions`](File::sortable_game_versions)
+const LOADER_TYPE_ID: ID = 68441;
+/// The `game_version_type_id` of environments in [`File.sortable_game_versions`](File::sortable_game_versions)
+const ENVIRONMENT_TYPE_ID: ID = 75208;
+
+impl File {
+    /// Split [`File.game_versions`](File::game_versions) into game versions, mod loaders, environments, and Java versions
+    ///
+    /// Entries are classified using their `game_version_type_id` in `sortable_game_versions` where available,
+    /// and by their name otherwise. Mod loaders and environments with names furse doesn't know
+    /// are kept separately as strings.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use furse::structures::{common_structs::ModLoaderType, FileId, ModId};
+    /// # tokio_test::block_on(async {
+    /// # let curseforge = furse::Furse::new(env!("CURSEFORGE_API_KEY"));
+    /// // Get the Terralith mod's v2.0.12 file
+    /// let file = curseforge.get_mod_file(ModId(513688), FileId(3606078)).await?;
+    /// let compatibility = file.compatibility();
+    /// assert!(compatibility.game_versions.contains(&"1.18.2".to_owned()));
+    /// assert!(compatibility.loaders.contains(&ModLoaderType::Fabric));
+    /// # Ok::<_, furse::Error>(()) }).unwrap()
+    /// ```
+    pub fn compatibility(&self) -> Compatibility {
+        let mut compatibility = Compatibility::default();
+        for name in &self.game_versions {
+            let type_id = self
+                .sortable_game_versions
+                .iter()
+                .find(|sortable| sortable.game_version_name == *name)
+                .and_then(|sortable| sortable.game_version_type_id);
+            match (
+                type_id,
+                ModLoaderType::from_name(name),
+                Environment::from_name(name),
+            ) {
+                (Some(LOADER_TYPE_ID) | None, Some(loader), _) => {
+                    compatibility.loaders.push(loader)
+                }
+                (Some(ENVIRONMENT_TYPE_ID) | None, _, Some(environment)) => {
+                    compatibility.environments.push(environment)
+                }
+                (Some(LOADER_TYPE_ID), _, _) => {
+                    compatibility.unrecognised_loaders.push(name.clone())
+                }
+                (Some(ENVIRONMENT_TYPE_ID), _, _) => {
+                    compatibility.unrecognised_environments.push(name.clone())
+                }
+                _ => match name
+                    .strip_prefix("Java ")
+                    .and_then(|version| version.parse().ok())
+                {
+                    Some(java) => compatibility.java_versions.push(java),
+                    None => compatibility.game_versions.push(name.clone()),
+                },
+            }
+        }
+        compatibility
+    }
+}
+
+/// A typed view of [`File.game_versions`](File::game_versions), as returned by [`File::compatibility()`]
+///
+/// Lists that are empty mean the file doesn't specify them, e.g. files that don't list an environment
+/// should be treated as working on both the client and server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Compatibility {
+    /// The game versions, e.g. `1.20.1`, which can be parsed using [`Compatibility::parsed_game_versions()`]
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<ModLoaderType>,
+    pub environments: Vec<Environment>,
+    /// The Java versions, e.g. `17` for `Java 17`
+    pub java_versions: Vec<u32>,
+    /// Entries typed as a mod loader whose name isn't recognised
+    pub unrecognised_loaders: Vec<String>,
+    /// Entries typed as an environment whose name isn't recognised
+    pub unrecognised_environments: Vec<String>,
+}
+
+impl Compatibility {
+    /// Parse the game versions, skipping ones that aren't Minecraft versions
+    pub fn parsed_game_versions(&self) -> Vec<GameVersion> {
+        self.game_versions
+            .iter()
+            .filter_map(|version| version.parse().ok())
+            .collect()
+    }
+
+    /// Whether the file works on `environment`, which is assumed if it doesn't list any
+    pub fn supports_environment(&self, environment: Environment) -> bool {
+        (self.environments.is_empty() && self.unrecognised_environments.is_empty())
+            || self.environments.contains(&environment)
+    }
+}
+
+/// The side of the game that a file is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Environment {
+    Client,
+    Server,
+}
+
+impl Environment {
+    /// Parse an environment name as it appears in [`File.game_versions`](File::game_versions), ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("client") {
+            Some(Self::Client)
+        } else if name.eq_ignore_ascii_case("server") {
+            Some(Self::Server)
+        } else {
+            None
+        }
+    }
+}
+
+/// The stability of a file, ordered from most to least stable
+#[derive(
+    Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[repr(u8)]
 pub enum FileReleaseType {
     Release = 1,
@@ -77,11 +200,14 @@ pub enum FileStatus {
 #[serde(rename_all = "camelCase")]
 pub struct FileIndex {
     pub game_version: String,
-    pub file_id: ID,
+    pub file_id: FileId,
     pub filename: String,
     pub release_type: FileReleaseType,
     pub game_version_type_id: Option<ID>,
     pub mod_loader: Option<ModLoaderType>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -89,6 +215,9 @@ pub struct FileIndex {
 pub struct FileHash {
     pub value: String,
     pub algo: HashAlgo,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
@@ -101,7 +230,7 @@ pub enum HashAlgo {
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDependency {
-    pub mod_id: ID,
+    pub mod_id: ModId,
     pub relation_type: FileRelationType,
 }
 
@@ -120,5 +249,8 @@ pub enum FileRelationType {
 #[serde(rename_all = "camelCase")]
 pub struct FileModule {
     pub name: String,
-    pub fingerprint: Number,
+    pub fingerprint: Fingerprint,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
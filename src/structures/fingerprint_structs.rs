@@ -1,4 +1,5 @@
-use super::{file_structs::File, *};
+use super::{file_structs::File, mod_structs::Mod, *};
+use std::path::PathBuf;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -7,19 +8,76 @@ pub struct FingerprintMatches {
     /// The matches found
     pub exact_matches: Vec<Match>,
     /// The fingerprints of the matches found
-    pub exact_fingerprints: Vec<Number>,
+    pub exact_fingerprints: Vec<Fingerprint>,
     pub partial_matches: Vec<Match>,
-    pub partial_match_fingerprints: std::collections::HashMap<String, Vec<Number>>,
+    pub partial_match_fingerprints: std::collections::HashMap<String, Vec<Fingerprint>>,
     /// The fingerprints that were requested
-    pub installed_fingerprints: Vec<Number>,
-    pub unmatched_fingerprints: Option<Vec<Number>>,
+    pub installed_fingerprints: Vec<Fingerprint>,
+    pub unmatched_fingerprints: Option<Vec<Fingerprint>>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Match {
     /// The ID of the mod
-    pub id: ID,
+    pub id: ModId,
+    pub file: File,
+    /// The mod's latest files
+    pub latest_files: Vec<File>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// The fingerprints of the files in a folder, used to identify unpacked content such as addons or resource packs
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderFingerprint {
+    #[serde(rename = "foldername")]
+    pub folder_name: String,
+    pub fingerprints: Vec<Fingerprint>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintFuzzyMatch {
+    /// The ID of the mod
+    pub id: ModId,
+    pub file: File,
+    /// The mod's latest files
+    pub latest_files: Vec<File>,
+    /// The fingerprints of the folder that matched
+    pub fingerprints: Vec<Fingerprint>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
+}
+
+/// A local file and what it was identified as by [`Furse::identify_files()`](crate::Furse::identify_files)
+#[derive(Debug, Clone)]
+pub struct IdentifiedFile {
+    pub path: PathBuf,
+    pub fingerprint: Fingerprint,
+    pub identification: Identification,
+}
+
+#[derive(Debug, Clone)]
+pub enum Identification {
+    /// The file is exactly the same as this CurseForge file
+    Exact(Box<IdentifiedMatch>),
+    /// The file was not found, but parts of it match these CurseForge files
+    Partial(Vec<IdentifiedMatch>),
+    /// The file is not known to CurseForge
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdentifiedMatch {
+    /// The mod that the file belongs to, or `None` if it could not be found
+    pub owner: Option<Mod>,
     pub file: File,
     /// The mod's latest files
     pub latest_files: Vec<File>,
@@ -6,12 +6,67 @@ pub mod mod_structs;
 pub type UtcTime = chrono::DateTime<chrono::Utc>;
 pub type Number = i64;
 pub type ID = i32;
+/// Unmodelled fields of an API response, kept so that new fields can be used before furse supports them
+pub type JsonMap = serde_json::Map<String, serde_json::Value>;
 
 use common_structs::*;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use url::Url;
 
+/// Define a transparent newtype around an integer so that different kinds of IDs can't be mixed up
+macro_rules! id_newtype {
+    ($(#[$attr:meta])* $name:ident($inner:ty)) => {
+        $(#[$attr])*
+        #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        impl From<$inner> for $name {
+            fn from(id: $inner) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+id_newtype!(
+    /// The ID of a mod, also called a project
+    ModId(ID)
+);
+id_newtype!(
+    /// The ID of a file belonging to a mod
+    FileId(ID)
+);
+id_newtype!(
+    /// The ID of a game, e.g. `432` for Minecraft
+    GameId(ID)
+);
+id_newtype!(
+    /// The ID of a category
+    CategoryId(ID)
+);
+id_newtype!(
+    /// The ID of a class, which is a top level category such as mods or resource packs
+    ClassId(ID)
+);
+id_newtype!(
+    /// A CurseForge fingerprint, calculated using [`cf_fingerprint()`](crate::cf_fingerprint)
+    Fingerprint(u32)
+);
+
 fn deserialise_optional_url<'de, D: serde::Deserializer<'de>>(
     de: D,
 ) -> Result<Option<Url>, D::Error> {
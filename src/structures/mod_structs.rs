@@ -6,8 +6,8 @@ use super::{
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Mod {
-    pub id: ID,
-    pub game_id: ID,
+    pub id: ModId,
+    pub game_id: GameId,
     pub name: String,
     /// The mod slug that would appear in the URL
     pub slug: String,
@@ -19,15 +19,15 @@ pub struct Mod {
     /// Whether the mod is included in the featured mods list
     pub is_featured: bool,
     /// The main category of the mod as it was chosen by the mod author
-    pub primary_category_id: ID,
+    pub primary_category_id: CategoryId,
     /// List of categories that this mod is related to
     pub categories: Vec<Category>,
     /// The ID of the class this mod belongs to
-    pub class_id: Option<ID>,
+    pub class_id: Option<ClassId>,
     pub authors: Vec<ModAuthor>,
     pub logo: Option<ModAsset>,
     pub screenshots: Vec<ModAsset>,
-    pub main_file_id: ID,
+    pub main_file_id: FileId,
     pub latest_files: Vec<File>,
     /// List of file related details for the latest files of the mod
     pub latest_files_indexes: Vec<FileIndex>,
@@ -42,6 +42,9 @@ pub struct Mod {
     pub is_available: bool,
     pub thumbs_up_count: Number,
     pub rating: Option<f64>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -55,6 +58,9 @@ pub struct ModLinks {
     pub issues_url: Option<Url>,
     #[serde(deserialize_with = "deserialise_optional_url")]
     pub source_url: Option<Url>,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
@@ -78,15 +84,21 @@ pub struct ModAuthor {
     pub id: ID,
     pub name: String,
     pub url: Url,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ModAsset {
     pub id: ID,
-    pub mod_id: ID,
+    pub mod_id: ModId,
     pub title: String,
     pub description: String,
     pub thumbnail_url: String,
     pub url: Url,
+    /// Fields returned by the API that furse doesn't model yet
+    #[serde(flatten)]
+    pub extra: JsonMap,
 }